@@ -1,7 +1,6 @@
 use limelightlib_rust::{LimelightClient, LimelightConfig};
 use std::error::Error;
 use tokio::time::Duration;
-use tracing_subscriber;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -16,6 +15,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         host: "192.168.1.181".to_string(),
         port: 5807,
         poll_interval_ms: 20,
+        ..Default::default()
     };
 
     let client = LimelightClient::new(config);
@@ -39,7 +39,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         match results.recv().await {
             Ok(result) => {
               
-                println!("Valid target: {}", result.v.unwrap_or(0.0) > 0.0);
+                println!("Valid target: {}", result.valid());
                 if let Some(tx) = result.tx {
                     println!("Target X offset: {:.2}°", tx);
                 }
@@ -62,8 +62,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 if let Some(botpose) = &result.botpose {
                     println!("Botpose: {:?}", botpose);
                 }
-                if let Some(botposeMT2) = &result.botposeMT2 {
-                    println!("BotposeMT2: {:?}", botposeMT2);
+                if let Some(botpose_mt2) = &result.botposeMT2 {
+                    println!("BotposeMT2: {:?}", botpose_mt2);
                 }
 
                 for br in &result.barcode {
@@ -112,6 +112,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     println!("Stopping client...");
     client.stop().await;
+    client.join().await;
 
     Ok(())
 }
\ No newline at end of file
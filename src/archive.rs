@@ -0,0 +1,90 @@
+use crate::{LimelightClient, LimelightError};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+
+pub struct SnapshotArchiveHandle {
+    stop_tx: oneshot::Sender<()>,
+    task: JoinHandle<usize>,
+}
+
+impl SnapshotArchiveHandle {
+    // Stops after the current pass and returns how many snapshots were archived
+    pub async fn stop(self) -> Result<usize, LimelightError> {
+        let _ = self.stop_tx.send(());
+        Ok(self.task.await?)
+    }
+}
+
+pub(crate) fn spawn(client: LimelightClient, dir: PathBuf, poll: Duration, delete_from_device: bool) -> SnapshotArchiveHandle {
+    let (stop_tx, stop_rx) = oneshot::channel();
+    let task = tokio::spawn(run(client, dir, poll, delete_from_device, stop_rx));
+    SnapshotArchiveHandle { stop_tx, task }
+}
+
+async fn run(
+    client: LimelightClient,
+    dir: PathBuf,
+    poll: Duration,
+    delete_from_device: bool,
+    mut stop_rx: oneshot::Receiver<()>,
+) -> usize {
+    let mut archived = HashSet::new();
+    let mut interval = tokio::time::interval(poll);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = &mut stop_rx => break,
+        }
+
+        let manifest = match client.get_snapshot_manifest().await {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                tracing::warn!("Failed to fetch snapshot manifest: {:?}", e);
+                continue;
+            }
+        };
+
+        for snapname in manifest {
+            if archived.contains(&snapname) {
+                continue;
+            }
+            // Never let a device-supplied name escape `dir`
+            let Some(file_name) = Path::new(&snapname).file_name() else {
+                tracing::warn!("Skipping snapshot with unusable name {:?}", snapname);
+                continue;
+            };
+            let path = dir.join(file_name);
+            if path.exists() {
+                archived.insert(snapname);
+                continue;
+            }
+
+            // Failures are retried on the next pass
+            let image = match client.download_snapshot(&snapname).await {
+                Ok(image) => image,
+                Err(e) => {
+                    tracing::warn!("Failed to download snapshot {}: {:?}", snapname, e);
+                    continue;
+                }
+            };
+            if let Err(e) = tokio::fs::write(&path, &image).await {
+                tracing::warn!("Failed to write snapshot {} to {:?}: {:?}", snapname, path, e);
+                continue;
+            }
+            tracing::debug!("Archived snapshot {} ({} bytes) to {:?}", snapname, image.len(), path);
+
+            if delete_from_device {
+                match client.delete_snapshot(&snapname).await {
+                    Ok(true) => {}
+                    Ok(false) => tracing::warn!("Device refused to delete archived snapshot {}", snapname),
+                    Err(e) => tracing::warn!("Failed to delete archived snapshot {}: {:?}", snapname, e),
+                }
+            }
+            archived.insert(snapname);
+        }
+    }
+    archived.len()
+}
@@ -0,0 +1,108 @@
+use crate::{LimelightClient, LimelightConfig, LimelightError, LimelightResult};
+use std::future::Future;
+use std::thread;
+use tokio::runtime::{Builder, Handle};
+use tokio::sync::oneshot;
+
+// Synchronous wrapper in the style of reqwest::blocking. The async client lives on a
+// current-thread runtime driven by a dedicated thread, so the poll loop keeps running
+// between calls. Calling these methods from inside an async context panics; dropping the
+// client there is fine.
+pub struct BlockingLimelightClient {
+    inner: LimelightClient,
+    handle: Handle,
+    shutdown: Option<oneshot::Sender<()>>,
+    runtime_thread: Option<thread::JoinHandle<()>>,
+}
+
+impl BlockingLimelightClient {
+    pub fn new(config: LimelightConfig) -> Result<Self, LimelightError> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        let handle = runtime.handle().clone();
+        let inner = LimelightClient::new(config);
+        let client = inner.clone();
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let runtime_thread = thread::Builder::new()
+            .name("limelight-blocking".into())
+            .spawn(move || {
+                runtime.block_on(async {
+                    let _ = shutdown_rx.await;
+                    client.stop().await;
+                    client.join().await;
+                });
+                tracing::debug!("Blocking client runtime stopped");
+            })?;
+
+        Ok(Self {
+            inner,
+            handle,
+            shutdown: Some(shutdown),
+            runtime_thread: Some(runtime_thread),
+        })
+    }
+
+    // The wrapped async client, for anything not mirrored here
+    pub fn client(&self) -> &LimelightClient {
+        &self.inner
+    }
+
+    pub fn start(&self) -> Result<(), LimelightError> {
+        self.run(|client| async move { client.start().await })
+    }
+
+    pub fn stop(&self) {
+        self.run(|client| async move {
+            client.stop().await;
+            client.join().await;
+        })
+    }
+
+    pub fn get_latest_result(&self) -> Option<LimelightResult> {
+        self.run(|client| async move { client.get_latest_result().await })
+    }
+
+    pub fn fetch_once(&self) -> Result<LimelightResult, LimelightError> {
+        self.run(|client| async move { client.fetch_once().await })
+    }
+
+    pub fn switch_pipeline(&self, index: u32) -> Result<bool, LimelightError> {
+        self.run(move |client| async move { client.switch_pipeline(index).await })
+    }
+
+    pub fn capture_snapshot(&self, snapname: &str) -> Result<bool, LimelightError> {
+        let snapname = snapname.to_string();
+        self.run(move |client| async move { client.capture_snapshot(&snapname).await })
+    }
+
+    // Runs the call on the runtime thread and waits for its output
+    fn run<F, Fut, T>(&self, call: F) -> T
+    where
+        F: FnOnce(LimelightClient) -> Fut,
+        Fut: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let future = call(self.inner.clone());
+        let (tx, rx) = oneshot::channel();
+        self.handle.spawn(async move {
+            let _ = tx.send(future.await);
+        });
+        rx.blocking_recv().expect("blocking client runtime stopped")
+    }
+}
+
+// The runtime thread stops the client on shutdown. Inside an async context the thread is
+// left to finish on its own, since joining it would block the caller's runtime.
+impl Drop for BlockingLimelightClient {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if Handle::try_current().is_ok() {
+            tracing::debug!("Dropped inside a runtime, not waiting for the blocking client to stop");
+            return;
+        }
+        if let Some(runtime_thread) = self.runtime_thread.take() {
+            let _ = runtime_thread.join();
+        }
+    }
+}
@@ -1,15 +1,69 @@
-use crate::{LimelightError, LimelightResult};
+use crate::archive::{self, SnapshotArchiveHandle};
+use crate::{average_poses, CameraCalibration, CommandOutcome, CommandRecord, ConnectionState, EndpointSupport, FieldMap, FirmwareInfo, HardwareReport, LatencyStats, LedMode, NeuralNetworkInfo, PipelineType, PollErrorKind, PollMetrics, SlowdownReason, StreamMode, TargetEvent, translation_spread, yaw_spread_degrees, Clock, IntervalClock, LimelightError, LimelightResult, LimelightStatus, MegaTagVersion, Pose3d, PoseOrigin, PoseReading};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures_util::{FutureExt, Stream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
 use reqwest::Client as HttpClient;
+use std::collections::{HashMap, VecDeque};
+#[cfg(feature = "mdns")]
+use std::net::IpAddr;
+use std::future::Future;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::{broadcast, RwLock};
-use tokio::time::{Duration, interval, Interval};  // Added Interval to imports
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::{broadcast, mpsc, watch, Notify, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 use serde_json::{json, Value};
 
+// The watch channel is always kept current. Watch mode makes it the primary feed: the
+// broadcast ring is still fed, so `subscribe()` and everything built on it keep working, but
+// only while it has receivers and without ever applying backpressure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChannelMode {
+    #[default]
+    Broadcast,
+    Watch,
+}
+
+// What the poll loop does when the broadcast ring is full because a subscriber is behind.
+// DropOldest never slows polling but the lagging subscriber sees `Lagged` and misses frames.
+// Backpressure pauses the loop until the slowest subscriber drains a slot, so nothing is
+// dropped unless it stays full past `timeout`; the cost is that one slow consumer delays
+// polling, and therefore every other consumer, by up to `timeout` per result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlowSubscriberPolicy {
+    #[default]
+    DropOldest,
+    Backpressure { timeout: Duration },
+}
+
 #[derive(Clone)]
 pub struct LimelightConfig {
     pub host: String,
     pub port: u16,
     pub poll_interval_ms: u64,
+    pub request_timeout_ms: u64,
+    pub upload_timeout_ms: Option<u64>,
+    pub max_response_bytes: usize,
+    pub max_targets_per_type: usize,
+    pub max_poll_restarts: u32,
+    pub max_backoff_ms: Option<u64>,
+    pub compress_pipeline_uploads: bool,
+    pub channel_mode: ChannelMode,
+    pub slow_subscriber_policy: SlowSubscriberPolicy,
+    pub command_history_len: usize,
+    pub headers: HashMap<String, String>,
+    pub user_agent: Option<String>,
 }
 
 impl Default for LimelightConfig {
@@ -19,30 +73,220 @@ impl Default for LimelightConfig {
             host: "10.0.0.2".to_string(),
             port: 5807,
             poll_interval_ms: 10,
+            request_timeout_ms: 100,
+            upload_timeout_ms: None,
+            max_response_bytes: 1024 * 1024,
+            max_targets_per_type: 256,
+            max_poll_restarts: 3,
+            max_backoff_ms: None,
+            compress_pipeline_uploads: false,
+            channel_mode: ChannelMode::Broadcast,
+            slow_subscriber_policy: SlowSubscriberPolicy::DropOldest,
+            command_history_len: 0,
+            headers: HashMap::new(),
+            user_agent: None,
+        }
+    }
+}
+
+impl LimelightConfig {
+    // FRC convention: team TEAM puts its Limelight at 10.TE.AM.11, so 1234 -> 10.12.34.11
+    // and 9 -> 10.0.9.11
+    pub fn from_team_number(team: u16) -> Self {
+        Self {
+            host: format!("10.{}.{}.11", team / 100, team % 100),
+            ..Default::default()
+        }
+    }
+
+    pub fn builder() -> LimelightConfigBuilder {
+        LimelightConfigBuilder::default()
+    }
+}
+
+// Starts from LimelightConfig::default(); build() rejects values polling could never work with
+#[derive(Clone, Default)]
+pub struct LimelightConfigBuilder {
+    config: LimelightConfig,
+}
+
+impl LimelightConfigBuilder {
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.config.host = host.into();
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.config.port = port;
+        self
+    }
+
+    pub fn poll_interval_ms(mut self, interval_ms: u64) -> Self {
+        self.config.poll_interval_ms = interval_ms;
+        self
+    }
+
+    pub fn build(self) -> Result<LimelightConfig, LimelightError> {
+        if self.config.host.trim().is_empty() {
+            return Err(LimelightError::ConfigError("Host cannot be empty".into()));
+        }
+        if self.config.poll_interval_ms == 0 {
+            return Err(LimelightError::ConfigError("Poll interval cannot be zero".into()));
+        }
+        Ok(self.config)
+    }
+}
+
+// Used when a 429 carries no usable Retry-After, and the most a header can make us wait
+const DEFAULT_RATE_LIMIT_PAUSE: Duration = Duration::from_secs(1);
+const MAX_RATE_LIMIT_PAUSE: Duration = Duration::from_secs(60);
+
+// Beyond this, latest_pose warns that a MegaTag2 pose was solved against a stale orientation
+const MT2_ORIENTATION_MAX_AGE: Duration = Duration::from_millis(500);
+
+// Read-only endpoints checked by probe_endpoints
+const PROBED_ENDPOINTS: &[&str] = &[
+    "results",
+    "status",
+    "hwreport",
+    "hostname",
+    "pipeline-default",
+    "pipeline-atindex?index=0",
+    "cal-default",
+    "cal-file",
+    "cal-eeprom",
+    "cal-latest",
+    "getnnmodelnames",
+    "getsnapsscriptnames",
+    "snapshotmanifest",
+];
+
+const CLOCK_OFFSET_SAMPLES: usize = 5;
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_millis(10);
+const CALIBRATION_POLL_INTERVAL: Duration = Duration::from_millis(250);
+// At most one webhook POST per interval; results in between are not reported
+const WEBHOOK_MIN_INTERVAL: Duration = Duration::from_secs(1);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_millis(500);
+const RESULT_CHANNEL_CAPACITY: usize = 100;
+// Results push endpoint, on the same host and port as the HTTP API
+const WEBSOCKET_RESULTS_PATH: &str = "results";
+// How long a quiet socket is read before re-checking whether stop() was called
+const WEBSOCKET_STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+type ResultCallback = Arc<dyn Fn(&LimelightResult) + Send + Sync>;
+type PollGate = Arc<dyn Fn() -> bool + Send + Sync>;
+
+// State carried between iterations of a single poll loop run
+#[derive(Default)]
+struct PollState {
+    last_ts: Option<f64>,
+    last_valid: Option<bool>,
+    connection: Option<ConnectionState>,
+}
+
+// Bounds applied to every /results response
+#[derive(Clone, Copy)]
+struct FetchLimits {
+    request_timeout: Duration,
+    max_response_bytes: usize,
+    max_targets_per_type: usize,
+}
+
+impl From<&LimelightConfig> for FetchLimits {
+    fn from(config: &LimelightConfig) -> Self {
+        Self {
+            request_timeout: Duration::from_millis(config.request_timeout_ms),
+            max_response_bytes: config.max_response_bytes,
+            max_targets_per_type: config.max_targets_per_type,
         }
     }
 }
 
+#[derive(Clone, Copy)]
+struct FallbackPose {
+    pose: Pose3d,
+    max_age: Duration,
+}
+
+#[derive(Clone)]
+struct CachedCalibration {
+    pipeline_id: Option<i32>,
+    calibration: CameraCalibration,
+}
+
+#[derive(Clone)]
 pub struct LimelightClient {
     config: Arc<RwLock<LimelightConfig>>,
     http_client: HttpClient,
     latest_result: Arc<RwLock<Option<LimelightResult>>>,
     running: Arc<RwLock<bool>>,
     result_tx: broadcast::Sender<LimelightResult>,
+    batch_tx: broadcast::Sender<Vec<LimelightResult>>,
+    target_event_tx: broadcast::Sender<TargetEvent>,
+    connection_tx: broadcast::Sender<ConnectionState>,
+    watch_tx: watch::Sender<Option<LimelightResult>>,
+    clock: Arc<dyn Clock>,
+    ts_discontinuities: Arc<RwLock<u64>>,
+    calibration_cache: Arc<RwLock<Option<CachedCalibration>>>,
+    #[cfg(feature = "mdns")]
+    resolved_host: Arc<RwLock<Option<(String, IpAddr)>>>,
+    last_valid_target: Arc<RwLock<Option<Instant>>>,
+    on_result: Arc<RwLock<Option<ResultCallback>>>,
+    poll_gate: Arc<RwLock<Option<PollGate>>>,
+    latest_result_at: Arc<RwLock<Option<Instant>>>,
+    fallback_pose: Arc<RwLock<Option<FallbackPose>>>,
+    poll_metrics: Arc<RwLock<PollMetrics>>,
+    orientation_updated_at: Arc<RwLock<Option<Instant>>>,
+    command_history: Arc<RwLock<VecDeque<CommandRecord>>>,
+    target_webhook: Arc<RwLock<Option<JoinHandle<()>>>>,
+    poll_task: Arc<RwLock<Option<JoinHandle<()>>>>,
+    // Woken whenever `running` goes false, so waits can end without polling the flag
+    stopped: Arc<Notify>,
+    supported_pipeline_types: Arc<RwLock<Option<Vec<PipelineType>>>>,
 }
 
 impl LimelightClient {
     pub fn new(config: LimelightConfig) -> Self {
+        Self::with_clock(config, IntervalClock)
+    }
+
+    pub fn with_clock(config: LimelightConfig, clock: impl Clock) -> Self {
         tracing::debug!("Creating new LimelightClient with config: host={}, port={}, interval={}ms", 
             config.host, config.port, config.poll_interval_ms);
-        let (result_tx, _) = broadcast::channel(100);
-        tracing::debug!("Created broadcast channel with capacity 100");
+        let (result_tx, _) = broadcast::channel(RESULT_CHANNEL_CAPACITY);
+        let (batch_tx, _) = broadcast::channel(100);
+        let (target_event_tx, _) = broadcast::channel(16);
+        let (connection_tx, _) = broadcast::channel(16);
+        let (watch_tx, _) = watch::channel(None);
+        tracing::debug!("Created broadcast channel with capacity {}", RESULT_CHANNEL_CAPACITY);
+        let http_client = build_http_client(&config);
         Self {
             config: Arc::new(RwLock::new(config)),
-            http_client: HttpClient::new(),
+            http_client,
             latest_result: Arc::new(RwLock::new(None)),
             running: Arc::new(RwLock::new(false)),
             result_tx,
+            batch_tx,
+            target_event_tx,
+            connection_tx,
+            watch_tx,
+            clock: Arc::new(clock),
+            ts_discontinuities: Arc::new(RwLock::new(0)),
+            calibration_cache: Arc::new(RwLock::new(None)),
+            #[cfg(feature = "mdns")]
+            resolved_host: Arc::new(RwLock::new(None)),
+            last_valid_target: Arc::new(RwLock::new(None)),
+            on_result: Arc::new(RwLock::new(None)),
+            poll_gate: Arc::new(RwLock::new(None)),
+            latest_result_at: Arc::new(RwLock::new(None)),
+            fallback_pose: Arc::new(RwLock::new(None)),
+            poll_metrics: Arc::new(RwLock::new(PollMetrics::default())),
+            orientation_updated_at: Arc::new(RwLock::new(None)),
+            command_history: Arc::new(RwLock::new(VecDeque::new())),
+            target_webhook: Arc::new(RwLock::new(None)),
+            poll_task: Arc::new(RwLock::new(None)),
+            stopped: Arc::new(Notify::new()),
+            supported_pipeline_types: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -74,6 +318,294 @@ impl LimelightClient {
         self.result_tx.subscribe()
     }
 
+    // Always holds the newest result and never lags
+    pub fn subscribe_watch(&self) -> watch::Receiver<Option<LimelightResult>> {
+        tracing::debug!("New subscriber added to watch channel");
+        self.watch_tx.subscribe()
+    }
+
+    // Resolves with the first result published after this call, never the cached one.
+    // Subscribes immediately, so a result arriving before the future is first polled still counts.
+    // Returns NotRunning if the client stops while waiting.
+    pub fn next_result(&self) -> impl Future<Output = Result<LimelightResult, LimelightError>> + Send + 'static {
+        let mut latest = self.watch_tx.subscribe();
+        let running = self.running.clone();
+        let stopped = self.stopped.clone();
+        async move {
+            let stop = stopped.notified();
+            tokio::pin!(stop);
+            stop.as_mut().enable();
+            if !*running.read().await {
+                return Err(LimelightError::NotRunning);
+            }
+            loop {
+                tokio::select! {
+                    changed = latest.changed() => changed.map_err(|_| LimelightError::NotRunning)?,
+                    _ = &mut stop => return Err(LimelightError::NotRunning),
+                }
+                if let Some(result) = latest.borrow_and_update().clone() {
+                    return Ok(result);
+                }
+            }
+        }
+    }
+
+    // Sends every broadcast result as one JSON datagram to `addr`, so other local processes
+    // can consume results without polling the camera themselves. Results too large for a
+    // datagram are logged and skipped. Abort the returned handle to stop.
+    pub async fn start_udp_broadcast(&self, addr: SocketAddr) -> Result<JoinHandle<()>, LimelightError> {
+        let bind_addr: SocketAddr = if addr.is_ipv4() { ([0, 0, 0, 0], 0).into() } else { ([0u16; 8], 0).into() };
+        let socket = UdpSocket::bind(bind_addr).await?;
+        socket.connect(addr).await?;
+        tracing::debug!("Forwarding results over UDP to {}", addr);
+
+        let mut results = self.subscribe();
+        Ok(tokio::spawn(async move {
+            loop {
+                let result = match results.recv().await {
+                    Ok(result) => result,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("UDP forwarder lagged, {} results were not sent", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                match serde_json::to_vec(&result) {
+                    Ok(payload) => {
+                        if let Err(e) = socket.send(&payload).await {
+                            tracing::warn!("Failed to send {} byte result to {}: {:?}", payload.len(), addr, e);
+                        }
+                    }
+                    Err(e) => tracing::warn!("Failed to serialize result for UDP: {:?}", e),
+                }
+            }
+        }))
+    }
+
+    // POSTs a compact JSON summary to `url` whenever a result with a valid target arrives, at
+    // most once per WEBHOOK_MIN_INTERVAL. Runs on its own task, so a slow or failing endpoint
+    // never delays polling. Replaces any webhook set earlier. The endpoint is usually a third
+    // party, so posts use a plain client without the configured headers or user agent.
+    pub async fn set_target_webhook(&self, url: String) {
+        tracing::debug!("Setting target webhook to {}", url);
+        let mut latest = self.watch_tx.subscribe();
+        let http_client = HttpClient::builder().timeout(WEBHOOK_TIMEOUT).build().unwrap_or_else(|e| {
+            tracing::warn!("Failed to build webhook HTTP client: {:?}", e);
+            HttpClient::new()
+        });
+        let task = tokio::spawn(async move {
+            let mut last_sent: Option<Instant> = None;
+            while latest.changed().await.is_ok() {
+                let summary = match latest.borrow_and_update().as_ref() {
+                    Some(result) if result.valid() => target_summary(result),
+                    _ => continue,
+                };
+                if last_sent.is_some_and(|at| at.elapsed() < WEBHOOK_MIN_INTERVAL) {
+                    continue;
+                }
+                last_sent = Some(Instant::now());
+                match http_client.post(&url).json(&summary).send().await {
+                    Ok(response) if !response.status().is_success() => {
+                        tracing::warn!("Target webhook {} returned {}", url, response.status());
+                    }
+                    Ok(_) => tracing::debug!("Posted target summary to webhook"),
+                    Err(e) => tracing::warn!("Target webhook {} failed: {:?}", url, e),
+                }
+            }
+        });
+        if let Some(previous) = self.target_webhook.write().await.replace(task) {
+            previous.abort();
+        }
+    }
+
+    pub async fn clear_target_webhook(&self) {
+        if let Some(task) = self.target_webhook.write().await.take() {
+            tracing::debug!("Clearing target webhook");
+            task.abort();
+        }
+    }
+
+    // Every poll's full result set. Devices that return a single object produce batches of one.
+    pub fn subscribe_batches(&self) -> broadcast::Receiver<Vec<LimelightResult>> {
+        tracing::debug!("New subscriber added to batch broadcast channel");
+        self.batch_tx.subscribe()
+    }
+
+    // Returns once the slowest receiver has freed a slot, or after `timeout`
+    async fn wait_for_broadcast_slot(&self, timeout: Duration, iteration: u64) {
+        let deadline = Instant::now() + timeout;
+        while self.result_tx.len() >= RESULT_CHANNEL_CAPACITY {
+            if Instant::now() >= deadline {
+                tracing::warn!("Slow subscriber did not catch up within {:?} on iteration {}, dropping oldest result", timeout, iteration);
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+
+    // Fires only when `v` changes. A target already visible on the first result counts as acquired.
+    pub fn subscribe_target_events(&self) -> broadcast::Receiver<TargetEvent> {
+        tracing::debug!("New subscriber added to target event channel");
+        self.target_event_tx.subscribe()
+    }
+
+    // Fires on transitions only: one message when the camera drops, one when it comes back
+    pub fn subscribe_connection_state(&self) -> broadcast::Receiver<ConnectionState> {
+        tracing::debug!("New subscriber added to connection state channel");
+        self.connection_tx.subscribe()
+    }
+
+    // Runs inside the poll loop after each successful fetch, before broadcasting.
+    // A slow callback stalls polling, so hand heavy work off to another task.
+    pub async fn set_on_result(&self, f: impl Fn(&LimelightResult) + Send + Sync + 'static) {
+        tracing::debug!("Setting on_result callback");
+        *self.on_result.write().await = Some(Arc::new(f));
+    }
+
+    pub async fn clear_on_result(&self) {
+        tracing::debug!("Clearing on_result callback");
+        *self.on_result.write().await = None;
+    }
+
+    // Checked after every tick; while it returns false the loop skips the HTTP request but
+    // keeps ticking, so polling resumes on the next tick after the gate opens
+    pub async fn set_poll_gate(&self, gate: impl Fn() -> bool + Send + Sync + 'static) {
+        tracing::debug!("Setting poll gate");
+        *self.poll_gate.write().await = Some(Arc::new(gate));
+    }
+
+    pub async fn clear_poll_gate(&self) {
+        tracing::debug!("Clearing poll gate");
+        *self.poll_gate.write().await = None;
+    }
+
+    // Forwards at most `max_hz` results per second; intermediate results are dropped and the newest wins
+    pub fn subscribe_throttled(&self, max_hz: f64) -> Result<mpsc::Receiver<LimelightResult>, LimelightError> {
+        if !(max_hz.is_finite() && max_hz > 0.0) {
+            return Err(LimelightError::ConfigError(format!("Throttle rate must be a positive number of Hz, got {}", max_hz)));
+        }
+        let period = Duration::from_secs_f64(1.0 / max_hz);
+        tracing::debug!("New throttled subscriber added at {}Hz", max_hz);
+
+        let mut results = self.result_tx.subscribe();
+        let (tx, rx) = mpsc::channel(1);
+        tokio::spawn(async move {
+            let mut pending: Option<LimelightResult> = None;
+            let mut next_send = Instant::now();
+            loop {
+                tokio::select! {
+                    received = results.recv() => match received {
+                        Ok(result) => pending = Some(result),
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    _ = tokio::time::sleep_until(next_send), if pending.is_some() => {}
+                }
+
+                if Instant::now() < next_send {
+                    continue;
+                }
+                if let Some(result) = pending.take() {
+                    next_send = Instant::now() + period;
+                    match tx.try_send(result) {
+                        Ok(()) => {}
+                        // Consumer hasn't taken the last one yet; retry next period with whatever is newest
+                        Err(mpsc::error::TrySendError::Full(result)) => pending = Some(result),
+                        Err(mpsc::error::TrySendError::Closed(_)) => break,
+                    }
+                }
+            }
+            tracing::debug!("Throttled subscriber task stopped");
+        });
+        Ok(rx)
+    }
+
+    // Forwards only results matching `filter`. The task ends when the receiver is dropped.
+    pub fn subscribe_filtered(
+        &self,
+        mut filter: impl FnMut(&LimelightResult) -> bool + Send + 'static,
+    ) -> mpsc::Receiver<LimelightResult> {
+        tracing::debug!("New filtered subscriber added");
+        let mut results = self.result_tx.subscribe();
+        let (tx, rx) = mpsc::channel(RESULT_CHANNEL_CAPACITY);
+        tokio::spawn(async move {
+            loop {
+                match results.recv().await {
+                    Ok(result) if filter(&result) => {
+                        if tx.send(result).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Filtered subscriber lagged, skipped {} results", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            tracing::debug!("Filtered subscriber task stopped");
+        });
+        rx
+    }
+
+    // Results with at least one fiducial whose ID is in `ids`
+    pub fn subscribe_for_tags(&self, ids: Vec<i32>) -> mpsc::Receiver<LimelightResult> {
+        self.subscribe_filtered(move |result| {
+            result.fiducial.iter().any(|f| f.f_id.is_some_and(|id| ids.contains(&id)))
+        })
+    }
+
+    // Forwards a result only when its content_hash() differs from the previous one, so a
+    // static scene produces a single message instead of one per poll
+    pub fn subscribe_distinct(&self) -> mpsc::Receiver<LimelightResult> {
+        let mut last_hash = None;
+        self.subscribe_filtered(move |result| {
+            let hash = result.content_hash();
+            last_hash.replace(hash) != Some(hash)
+        })
+    }
+
+    // subscribe() as a Stream. A consumer that falls behind skips the frames it missed, as
+    // the raw receiver does, and the stream ends only when the client is dropped.
+    pub fn get_results_stream(&self) -> impl Stream<Item = LimelightResult> {
+        BroadcastStream::new(self.result_tx.subscribe()).filter_map(|received| async move {
+            match received {
+                Ok(result) => Some(result),
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    tracing::debug!("Results stream lagged, skipped {} results", skipped);
+                    None
+                }
+            }
+        })
+    }
+
+    // Yields (pose, unix_seconds) for each result with a valid pose, back-dated by the
+    // result's capture + pipeline latency
+    pub fn pose_stream(&self, origin: PoseOrigin, version: MegaTagVersion) -> impl Stream<Item = (Pose3d, f64)> {
+        let results = self.result_tx.subscribe();
+        futures_util::stream::unfold(results, move |mut results| async move {
+            loop {
+                match results.recv().await {
+                    Ok(result) => {
+                        let received_at = unix_seconds();
+                        if result.botpose_tagcount.unwrap_or(0) == 0 {
+                            continue;
+                        }
+                        let Some(pose) = result.pose(origin, version) else {
+                            continue;
+                        };
+                        let latency_s = result.total_latency_ms().unwrap_or(0.0) / 1000.0;
+                        return Some(((pose, received_at - latency_s), results));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Pose stream lagged, skipped {} results", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
     pub async fn start(&self) -> Result<(), LimelightError> {
         tracing::debug!("Attempting to start LimelightClient");
         let mut running = self.running.write().await;
@@ -84,90 +616,686 @@ impl LimelightClient {
         tracing::debug!("Setting running state to true");
         *running = true;
         
-        let config = self.config.clone();
-        let http_client = self.http_client.clone();
-        let latest_result = self.latest_result.clone();
-        let result_tx = self.result_tx.clone();
-        let running = self.running.clone();
+        let supervisor = self.clone();
+        let task = tokio::spawn(async move {
+            supervisor.supervise().await;
+        });
+        self.replace_poll_task(task).await;
 
-        tokio::spawn(async move {
-            tracing::debug!("Spawned polling task");
-            let config_read = config.read().await;
-            let mut interval_timer = interval(Duration::from_millis(config_read.poll_interval_ms));
-            let base_url = format!("http://{}:{}", config_read.host, config_read.port);
-            tracing::debug!("Starting polling loop with URL: {}, interval: {}ms", 
-                base_url, config_read.poll_interval_ms);
-            drop(config_read);
-
-            let mut last_interval_ms = 0;
-            let mut iteration = 0u64;
-            while *running.read().await {
-                iteration += 1;
-                tracing::debug!("Poll iteration {}", iteration);
-                interval_timer.tick().await;
-
-                // Only recreate the interval if the poll rate has changed
-                let current_config = config.read().await;
-                if current_config.poll_interval_ms != last_interval_ms {
-                    tracing::debug!("Poll rate changed from {}ms to {}ms", last_interval_ms, current_config.poll_interval_ms);
-                    interval_timer = interval(Duration::from_millis(current_config.poll_interval_ms));
-                    last_interval_ms = current_config.poll_interval_ms;
+        tracing::debug!("Client started successfully");
+        Ok(())
+    }
+
+    // Alternative to start() where the camera pushes results over a WebSocket. Results feed
+    // the same cache and channels as polling. If the upgrade fails this falls back to start(),
+    // and if the socket drops later HTTP polling takes over.
+    pub async fn start_websocket(&self) -> Result<(), LimelightError> {
+        if *self.running.read().await {
+            tracing::debug!("Client already running, ignoring start_websocket request");
+            return Ok(());
+        }
+
+        let base_url = self.base_url().await?;
+        let url = format!("ws{}/{}", base_url.trim_start_matches("http"), WEBSOCKET_RESULTS_PATH);
+        tracing::debug!("Connecting to results WebSocket at {}", url);
+        let socket = match tokio::time::timeout(self.request_timeout().await, tokio_tungstenite::connect_async(&url)).await {
+            Ok(Ok((socket, _))) => socket,
+            Ok(Err(e)) => {
+                tracing::warn!("WebSocket upgrade to {} failed, falling back to polling: {}", url, e);
+                return self.start().await;
+            }
+            Err(_) => {
+                tracing::warn!("WebSocket upgrade to {} timed out, falling back to polling", url);
+                return self.start().await;
+            }
+        };
+
+        let mut running = self.running.write().await;
+        if *running {
+            tracing::debug!("Client started while connecting, dropping WebSocket");
+            return Ok(());
+        }
+        *running = true;
+        drop(running);
+
+        let client = self.clone();
+        let task = tokio::spawn(async move {
+            client.stream_websocket(socket).await;
+            if *client.running.read().await {
+                tracing::warn!("Results WebSocket closed, falling back to HTTP polling");
+                client.supervise().await;
+            }
+        });
+        self.replace_poll_task(task).await;
+
+        tracing::debug!("Client started successfully over WebSocket");
+        Ok(())
+    }
+
+    async fn stream_websocket(&self, mut socket: WebSocketStream<MaybeTlsStream<TcpStream>>) {
+        let mut state = PollState::default();
+        let mut frame = 0u64;
+        while *self.running.read().await {
+            let message = match tokio::time::timeout(WEBSOCKET_STOP_CHECK_INTERVAL, socket.next()).await {
+                Err(_) => continue,
+                Ok(None) => break,
+                Ok(Some(Err(e))) => {
+                    tracing::error!("Results WebSocket failed after {} frames: {:?}", frame, e);
+                    self.update_connection_state(&mut state, false);
+                    break;
+                }
+                Ok(Some(Ok(message))) => message,
+            };
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Binary(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Message::Close(close) => {
+                    tracing::debug!("Results WebSocket closed by device: {:?}", close);
+                    self.update_connection_state(&mut state, false);
+                    break;
+                }
+                _ => continue,
+            };
+            frame += 1;
+
+            let limits = FetchLimits::from(&*self.config.read().await);
+            if text.len() > limits.max_response_bytes {
+                tracing::error!("WebSocket frame of {} bytes exceeds limit of {} bytes", text.len(), limits.max_response_bytes);
+                self.poll_metrics.write().await.record_error(PollErrorKind::Other);
+                continue;
+            }
+            let poll_gate = self.poll_gate.read().await.clone();
+            if poll_gate.is_some_and(|gate| !gate()) {
+                tracing::trace!("Poll gate closed, dropping WebSocket frame {}", frame);
+                continue;
+            }
+            match Self::parse_result_batch(&text, limits) {
+                Ok(batch) => {
+                    // Nothing was requested, so there is no fetch time to report
+                    self.poll_metrics.write().await.record_success(Duration::ZERO);
+                    self.update_connection_state(&mut state, true);
+                    for result in &batch {
+                        self.handle_result(result.clone(), &mut state, frame).await;
+                    }
+                    if !batch.is_empty() {
+                        let _ = self.batch_tx.send(batch);
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Error parsing WebSocket frame {}: {:?}", frame, e);
+                    self.poll_metrics.write().await.record_error(PollErrorKind::classify(&e));
+                }
+            }
+        }
+        if let Err(e) = socket.close(None).await {
+            tracing::debug!("Results WebSocket close failed: {:?}", e);
+        }
+        tracing::debug!("Results WebSocket stopped after {} frames", frame);
+    }
+
+    // A task left over from before a stop() may not have noticed `running` flip back, so it is
+    // aborted rather than left polling alongside the new one
+    async fn replace_poll_task(&self, task: JoinHandle<()>) {
+        if let Some(previous) = self.poll_task.write().await.replace(task) {
+            tracing::debug!("Aborting previous poll task");
+            previous.abort();
+        }
+    }
+
+    // Reruns the poll loop if it exits or panics while the client is still meant to be running.
+    // The loop runs inside this task so aborting the task stops it too.
+    async fn supervise(&self) {
+        let mut restarts = 0u32;
+        loop {
+            let outcome = AssertUnwindSafe(self.poll_loop()).catch_unwind().await;
+
+            if !*self.running.read().await {
+                tracing::debug!("Poll loop exited after stop");
+                break;
+            }
+            match outcome {
+                Err(_) => tracing::warn!("Poll loop panicked"),
+                Ok(()) => tracing::warn!("Poll loop exited unexpectedly"),
+            }
+
+            let max_restarts = self.config.read().await.max_poll_restarts;
+            if restarts >= max_restarts {
+                tracing::error!("Poll loop died {} times, giving up", restarts + 1);
+                *self.running.write().await = false;
+                self.stopped.notify_waiters();
+                break;
+            }
+            restarts += 1;
+            tracing::warn!("Restarting poll loop ({}/{})", restarts, max_restarts);
+        }
+    }
+
+    async fn poll_loop(&self) {
+        tracing::debug!("Polling task running");
+        let config_read = self.config.read().await;
+        let mut interval_timer = self.clock.ticker(Duration::from_millis(config_read.poll_interval_ms));
+        let base_url = format!("http://{}:{}", config_read.host, config_read.port);
+        tracing::debug!("Starting polling loop with URL: {}, interval: {}ms", 
+            base_url, config_read.poll_interval_ms);
+        drop(config_read);
+
+        let mut last_interval_ms = 0;
+        let mut state = PollState::default();
+        let mut iteration = 0u64;
+        let mut consecutive_failures = 0u32;
+        while *self.running.read().await {
+            iteration += 1;
+            tracing::debug!("Poll iteration {}", iteration);
+            interval_timer.tick().await;
+
+            // Only recreate the interval if the poll rate has changed
+            let current_config = self.config.read().await;
+            if current_config.poll_interval_ms != last_interval_ms {
+                tracing::debug!("Poll rate changed from {}ms to {}ms", last_interval_ms, current_config.poll_interval_ms);
+                interval_timer = self.clock.ticker(Duration::from_millis(current_config.poll_interval_ms));
+                last_interval_ms = current_config.poll_interval_ms;
+            }
+            let limits = FetchLimits::from(&*current_config);
+            let max_backoff_ms = current_config.max_backoff_ms;
+            drop(current_config);
+            let poll_gate = self.poll_gate.read().await.clone();
+            if poll_gate.is_some_and(|gate| !gate()) {
+                tracing::trace!("Poll gate closed, skipping iteration {}", iteration);
+                continue;
+            }
+            let base_url = match self.base_url().await {
+                Ok(base_url) => base_url,
+                Err(e) => {
+                    tracing::error!("Error resolving host on iteration {}: {:?}", iteration, e);
+                    continue;
+                }
+            };
+
+            let fetch_started = Instant::now();
+            match Self::fetch_result_batch(&self.http_client, &base_url, limits).await {
+                Ok(batch) => {
+                    tracing::debug!("Successfully fetched {} results on iteration {}", batch.len(), iteration);
+                    self.poll_metrics.write().await.record_success(fetch_started.elapsed());
+                    self.update_connection_state(&mut state, true);
+                    if consecutive_failures > 0 {
+                        tracing::debug!("Fetch recovered after {} consecutive failures", consecutive_failures);
+                        consecutive_failures = 0;
+                    }
+                    for result in &batch {
+                        self.handle_result(result.clone(), &mut state, iteration).await;
+                    }
+                    if !batch.is_empty() {
+                        let _ = self.batch_tx.send(batch);
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Error fetching results on iteration {}: {:?}", iteration, e);
+                    self.poll_metrics.write().await.record_error(PollErrorKind::classify(&e));
+                    self.update_connection_state(&mut state, false);
+                    if matches!(&e, LimelightError::HttpError(err) if err.is_connect()) {
+                        self.forget_resolved_host().await;
+                    }
+                    if let LimelightError::RateLimited(retry_after) = e {
+                        let pause = retry_after.unwrap_or(DEFAULT_RATE_LIMIT_PAUSE).min(MAX_RATE_LIMIT_PAUSE);
+                        tracing::warn!("Pausing poll loop for {:?} after rate limit", pause);
+                        self.pause_unless_stopped(pause).await;
+                    } else if let Some(max_backoff_ms) = max_backoff_ms {
+                        consecutive_failures = consecutive_failures.saturating_add(1);
+                        let pause = backoff_pause(last_interval_ms, max_backoff_ms, consecutive_failures);
+                        if !pause.is_zero() {
+                            tracing::debug!("Backing off {:?} after {} consecutive failures", pause, consecutive_failures);
+                            self.pause_unless_stopped(pause).await;
+                        }
+                    }
                 }
-                let base_url = format!("http://{}:{}", current_config.host, current_config.port);
-                drop(current_config);
+            }
+        }
+        tracing::debug!("Polling loop stopped after {} iterations", iteration);
+    }
+
+    // Sleeps for `pause`, returning early once stop() is called so join() isn't held up
+    async fn pause_unless_stopped(&self, pause: Duration) {
+        let stop = self.stopped.notified();
+        tokio::pin!(stop);
+        stop.as_mut().enable();
+        if !*self.running.read().await {
+            return;
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(pause) => {}
+            _ = stop => tracing::debug!("Poll loop pause cut short by stop"),
+        }
+    }
+
+    fn update_connection_state(&self, state: &mut PollState, fetched: bool) {
+        let next = match (state.connection, fetched) {
+            (None, true) => ConnectionState::Connected,
+            (Some(ConnectionState::Disconnected), true) => ConnectionState::Reconnected,
+            (Some(ConnectionState::Connected | ConnectionState::Reconnected), false) => ConnectionState::Disconnected,
+            _ => return,
+        };
+        tracing::debug!("Connection state changed to {:?}", next);
+        state.connection = Some(next);
+        let _ = self.connection_tx.send(next);
+    }
+
+    async fn handle_result(&self, result: LimelightResult, state: &mut PollState, iteration: u64) {
+        tracing::trace!("Result details: {:?}", result);
+
+        // A backward jump in ts means the camera rebooted or its clock wrapped
+        if let (Some(prev_ts), Some(ts)) = (state.last_ts, result.ts) {
+            if ts < prev_ts {
+                tracing::warn!("Timestamp discontinuity on iteration {}: ts went from {} to {}", iteration, prev_ts, ts);
+                *self.ts_discontinuities.write().await += 1;
+            }
+        }
+        if result.ts.is_some() {
+            state.last_ts = result.ts;
+        }
+
+        let valid = result.valid();
+        if valid {
+            *self.last_valid_target.write().await = Some(Instant::now());
+        }
+        if state.last_valid.unwrap_or(false) != valid {
+            let event = if valid { TargetEvent::Acquired } else { TargetEvent::Lost };
+            tracing::debug!("Target event on iteration {}: {:?}", iteration, event);
+            let _ = self.target_event_tx.send(event);
+        }
+        state.last_valid = Some(valid);
+        
+        tracing::debug!("Updating latest_result");
+        *self.latest_result.write().await = Some(result.clone());
+        *self.latest_result_at.write().await = Some(Instant::now());
 
-                match Self::fetch_results(&http_client, &base_url).await {
+        let on_result = self.on_result.read().await.clone();
+        if let Some(callback) = on_result {
+            callback(&result);
+        }
+        
+        let (channel_mode, slow_subscriber_policy) = {
+            let config = self.config.read().await;
+            (config.channel_mode, config.slow_subscriber_policy)
+        };
+        self.watch_tx.send_replace(Some(result.clone()));
+        if channel_mode == ChannelMode::Watch {
+            if self.result_tx.receiver_count() > 0 {
+                let _ = self.result_tx.send(result);
+            }
+            return;
+        }
+
+        if let SlowSubscriberPolicy::Backpressure { timeout } = slow_subscriber_policy {
+            self.wait_for_broadcast_slot(timeout, iteration).await;
+        }
+
+        tracing::debug!("Broadcasting result to {} receivers", self.result_tx.receiver_count());
+        if let Err(e) = self.result_tx.send(result) {
+            tracing::error!("Error broadcasting result on iteration {}: {:?}", iteration, e);
+        } else {
+            tracing::debug!("Successfully broadcast result");
+        }
+    }
+
+    pub async fn stop(&self) {
+        tracing::debug!("Attempting to stop LimelightClient");
+        let mut running = self.running.write().await;
+        *running = false;
+        self.stopped.notify_waiters();
+        tracing::debug!("Client stopped, running state set to false");
+    }
+
+    // Waits for the poll task to exit. After stop() that happens once the loop finishes its
+    // current iteration; without stop() it only returns if the loop gives up restarting.
+    pub async fn join(&self) {
+        let Some(task) = self.poll_task.write().await.take() else {
+            tracing::debug!("No poll task to join");
+            return;
+        };
+        match task.await {
+            Ok(()) => tracing::debug!("Poll task joined"),
+            Err(e) => tracing::warn!("Poll task ended abnormally: {:?}", e),
+        }
+    }
+
+    // Without a fallback this is just the latest result's pose. With one, a pose older than
+    // `max_age` (or missing) is replaced by the fallback, flagged as stale.
+    pub async fn latest_pose(&self, origin: PoseOrigin, version: MegaTagVersion) -> Option<PoseReading> {
+        let fallback = *self.fallback_pose.read().await;
+        let received_at = *self.latest_result_at.read().await;
+        let fresh = self.latest_result.read().await.as_ref()
+            .filter(|result| result.botpose_tagcount.unwrap_or(0) > 0)
+            .and_then(|result| result.pose(origin, version));
+        if fresh.is_some() && version == MegaTagVersion::MegaTag2 && !self.megatag2_orientation_fresh(MT2_ORIENTATION_MAX_AGE).await {
+            tracing::warn!("Returning a MegaTag2 pose without a robot orientation update in the last {:?}", MT2_ORIENTATION_MAX_AGE);
+        }
+
+        match (fresh, fallback) {
+            (Some(pose), None) => Some(PoseReading { pose, stale: false }),
+            (Some(pose), Some(fallback)) if received_at.is_some_and(|at| at.elapsed() <= fallback.max_age) => {
+                Some(PoseReading { pose, stale: false })
+            }
+            (_, Some(fallback)) => {
+                tracing::debug!("No fresh vision pose, returning fallback");
+                Some(PoseReading { pose: fallback.pose, stale: true })
+            }
+            (None, None) => None,
+        }
+    }
+
+    pub async fn set_fallback_pose(&self, pose: Pose3d, max_age: Duration) {
+        tracing::debug!("Setting fallback pose {:?} with max age {:?}", pose, max_age);
+        *self.fallback_pose.write().await = Some(FallbackPose { pose, max_age });
+    }
+
+    pub async fn clear_fallback_pose(&self) {
+        *self.fallback_pose.write().await = None;
+    }
+
+    pub async fn poll_metrics(&self) -> PollMetrics {
+        self.poll_metrics.read().await.clone()
+    }
+
+    // False when results arrive noticeably slower than poll_interval_ms asks for
+    pub async fn is_keeping_up(&self) -> bool {
+        let requested = self.config.read().await.poll_interval_ms;
+        self.poll_metrics.read().await.is_keeping_up(requested)
+    }
+
+    // None while keeping up; otherwise the most likely cause of the slowdown
+    pub async fn slowdown_reason(&self) -> Option<SlowdownReason> {
+        let requested = self.config.read().await.poll_interval_ms;
+        self.poll_metrics.read().await.slowdown_reason(requested)
+    }
+
+    // None until a result with a valid target has been seen
+    pub async fn time_since_valid_target(&self) -> Option<Duration> {
+        self.last_valid_target.read().await.map(|seen| seen.elapsed())
+    }
+
+    pub async fn ts_discontinuity_count(&self) -> u64 {
+        *self.ts_discontinuities.read().await
+    }
+
+    pub async fn wait_for_multitag_pose(&self, min_tags: i32, timeout: Duration) -> Result<Pose3d, LimelightError> {
+        if !*self.running.read().await {
+            return Err(LimelightError::NotRunning);
+        }
+
+        tracing::debug!("Waiting up to {:?} for a botpose with at least {} tags", timeout, min_tags);
+        let mut results = self.subscribe();
+        tokio::time::timeout(timeout, async {
+            loop {
+                match results.recv().await {
                     Ok(result) => {
-                        tracing::debug!("Successfully fetched results on iteration {}", iteration);
-                        tracing::trace!("Result details: {:?}", result);
-                        
-                        tracing::debug!("Updating latest_result");
-                        *latest_result.write().await = Some(result.clone());
-                        
-                        tracing::debug!("Broadcasting result to {} receivers", result_tx.receiver_count());
-                        if let Err(e) = result_tx.send(result) {
-                            tracing::error!("Error broadcasting result on iteration {}: {:?}", iteration, e);
-                        } else {
-                            tracing::debug!("Successfully broadcast result");
+                        if result.botpose_tagcount.unwrap_or(0) < min_tags {
+                            continue;
+                        }
+                        if let Some(pose) = result.botpose.as_deref().and_then(Pose3d::from_array) {
+                            return Ok(pose);
                         }
                     }
-                    Err(e) => {
-                        tracing::error!("Error fetching results on iteration {}: {:?}", iteration, e);
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Multitag wait lagged, skipped {} results", skipped);
                     }
+                    Err(broadcast::error::RecvError::Closed) => return Err(LimelightError::NotRunning),
                 }
             }
-            tracing::debug!("Polling loop stopped after {} iterations", iteration);
-        });
+        })
+        .await
+        .map_err(|_| LimelightError::TimeoutError)?
+    }
+
+    // A single /results request, independent of the poll loop: nothing is cached or broadcast
+    pub async fn fetch_once(&self) -> Result<LimelightResult, LimelightError> {
+        let limits = FetchLimits::from(&*self.config.read().await);
+        let base_url = self.base_url().await?;
+        Self::fetch_results(&self.http_client, &base_url, limits).await
+    }
+
+    // Device clock in seconds when a fresh /results response was published. `ts` is the
+    // frame's capture time, so the reported capture + pipeline latency is added to it.
+    pub async fn get_device_time(&self) -> Result<f64, LimelightError> {
+        let result = self.fetch_once().await?;
+        let latency_ms = result.total_latency_ms().unwrap_or(0.0);
+        result.ts
+            .map(|ts| (ts + latency_ms) / 1000.0)
+            .ok_or_else(|| LimelightError::UnexpectedResponse("Device result has no ts".into()))
+    }
+
+    // Device time minus local unix time, in seconds. Each sample assumes the device read its
+    // clock halfway through the round trip; the sample with the shortest round trip wins.
+    pub async fn estimate_clock_offset(&self) -> Result<f64, LimelightError> {
+        let mut best: Option<(f64, f64)> = None;
+        for _ in 0..CLOCK_OFFSET_SAMPLES {
+            let sent = unix_seconds();
+            let device = self.get_device_time().await?;
+            let received = unix_seconds();
+            let round_trip = received - sent;
+            let offset = device - (sent + received) / 2.0;
+            tracing::debug!("Clock offset sample: {:.6}s (round trip {:.3}ms)", offset, round_trip * 1000.0);
+            if best.is_none_or(|(best_round_trip, _)| round_trip < best_round_trip) {
+                best = Some((round_trip, offset));
+            }
+        }
+        Ok(best.map(|(_, offset)| offset).unwrap_or_default())
+    }
+
+    // Issues `samples` sequential /results requests, timing each round trip alongside the
+    // camera-reported capture + pipeline latency
+    pub async fn measure_latency(&self, samples: usize) -> Result<LatencyStats, LimelightError> {
+        if samples == 0 {
+            return Err(LimelightError::ConfigError("Sample count cannot be zero".into()));
+        }
+
+        let limits = FetchLimits::from(&*self.config.read().await);
+        let mut latencies = Vec::with_capacity(samples);
+        let mut round_trip_total_ms = 0.0;
+        for _ in 0..samples {
+            let base_url = self.base_url().await?;
+            let sent_at = Instant::now();
+            let result = Self::fetch_results(&self.http_client, &base_url, limits).await?;
+            round_trip_total_ms += sent_at.elapsed().as_secs_f64() * 1000.0;
+            if let Some(latency) = result.total_latency_ms() {
+                latencies.push(latency);
+            }
+        }
+
+        if latencies.is_empty() {
+            return Err(LimelightError::UnexpectedResponse("Device did not report capture/pipeline latency".into()));
+        }
+        let n = latencies.len() as f64;
+        let mean_ms = latencies.iter().sum::<f64>() / n;
+        let variance = latencies.iter().map(|l| (l - mean_ms).powi(2)).sum::<f64>() / n;
+        let stats = LatencyStats {
+            samples: latencies.len(),
+            min_ms: latencies.iter().copied().fold(f64::INFINITY, f64::min),
+            max_ms: latencies.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            mean_ms,
+            stddev_ms: variance.sqrt(),
+            mean_round_trip_ms: round_trip_total_ms / samples as f64,
+        };
+        tracing::debug!("Measured latency: {:?}", stats);
+        Ok(stats)
+    }
+
+    pub async fn averaged_botpose(
+        &self,
+        origin: PoseOrigin,
+        version: MegaTagVersion,
+        samples: usize,
+        timeout: Duration,
+    ) -> Result<Pose3d, LimelightError> {
+        const MAX_TRANSLATION_SPREAD_M: f64 = 0.25;
+        const MAX_YAW_SPREAD_DEG: f64 = 5.0;
+
+        if samples == 0 {
+            return Err(LimelightError::ConfigError("Sample count cannot be zero".into()));
+        }
+        if !*self.running.read().await {
+            return Err(LimelightError::NotRunning);
+        }
+
+        tracing::debug!("Collecting {} {:?}/{:?} poses for averaging", samples, origin, version);
+        let mut results = self.subscribe();
+        let mut poses = Vec::with_capacity(samples);
+        tokio::time::timeout(timeout, async {
+            while poses.len() < samples {
+                match results.recv().await {
+                    Ok(result) => {
+                        if result.botpose_tagcount.unwrap_or(0) == 0 {
+                            continue;
+                        }
+                        if let Some(pose) = result.pose(origin, version) {
+                            poses.push(pose);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Pose averaging lagged, skipped {} results", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return Err(LimelightError::NotRunning),
+                }
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|_| LimelightError::TimeoutError)??;
+
+        let mean = average_poses(&poses).ok_or(LimelightError::TimeoutError)?;
+        let translation = translation_spread(&poses, &mean);
+        let yaw = yaw_spread_degrees(&poses);
+        tracing::debug!("Averaged {} poses, translation spread {:.3}m, yaw spread {:.2}deg", poses.len(), translation, yaw);
+        if translation > MAX_TRANSLATION_SPREAD_M || yaw > MAX_YAW_SPREAD_DEG {
+            return Err(LimelightError::InconsistentPose(format!(
+                "translation spread {:.3}m (max {}), yaw spread {:.2}deg (max {})",
+                translation, MAX_TRANSLATION_SPREAD_M, yaw, MAX_YAW_SPREAD_DEG
+            )));
+        }
+        Ok(mean)
+    }
+
+    async fn build_url(&self, endpoint: &str) -> Result<String, LimelightError> {
+        Ok(format!("{}/{}", self.base_url().await?, endpoint))
+    }
+
+    async fn base_url(&self) -> Result<String, LimelightError> {
+        let config = self.config.read().await;
+        let (host, port) = (config.host.clone(), config.port);
+        drop(config);
+        Ok(format!("http://{}:{}", self.resolve_host(&host).await?, port))
+    }
+
+    // `.local` hosts are resolved over mDNS once and cached until a connection fails
+    #[cfg(feature = "mdns")]
+    async fn resolve_host(&self, host: &str) -> Result<String, LimelightError> {
+        const MDNS_RESOLVE_TIMEOUT: Duration = Duration::from_secs(2);
+        if !crate::mdns::is_mdns_host(host) {
+            return Ok(host.to_string());
+        }
+
+        let cached = self.resolved_host.read().await.clone();
+        let addr = match cached {
+            Some((cached_host, addr)) if cached_host == host => addr,
+            _ => {
+                let addr = crate::mdns::resolve(host, MDNS_RESOLVE_TIMEOUT).await?;
+                *self.resolved_host.write().await = Some((host.to_string(), addr));
+                addr
+            }
+        };
+        Ok(match addr {
+            IpAddr::V4(v4) => v4.to_string(),
+            IpAddr::V6(v6) => format!("[{}]", v6),
+        })
+    }
 
-        tracing::debug!("Client started successfully");
-        Ok(())
+    #[cfg(not(feature = "mdns"))]
+    async fn resolve_host(&self, host: &str) -> Result<String, LimelightError> {
+        Ok(host.to_string())
     }
 
-    pub async fn stop(&self) {
-        tracing::debug!("Attempting to stop LimelightClient");
-        let mut running = self.running.write().await;
-        *running = false;
-        tracing::debug!("Client stopped, running state set to false");
+    #[cfg(feature = "mdns")]
+    async fn forget_resolved_host(&self) {
+        if self.resolved_host.write().await.take().is_some() {
+            tracing::debug!("Connection failed, will re-resolve mDNS hostname");
+        }
+    }
+
+    #[cfg(not(feature = "mdns"))]
+    async fn forget_resolved_host(&self) {}
+
+    async fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.config.read().await.request_timeout_ms)
     }
 
-    async fn build_url(&self, endpoint: &str) -> String {
+    // Raw uploads can be megabytes; they fall back to request_timeout_ms when unset
+    async fn upload_timeout(&self) -> Duration {
         let config = self.config.read().await;
-        format!("http://{}:{}/{}", config.host, config.port, endpoint)
+        Duration::from_millis(config.upload_timeout_ms.unwrap_or(config.request_timeout_ms))
     }
 
     async fn get_json<T: serde::de::DeserializeOwned>(&self, endpoint: &str) -> Result<T, LimelightError> {
-        let url = self.build_url(endpoint).await;
+        let url = self.build_url(endpoint).await?;
+        let max_response_bytes = self.config.read().await.max_response_bytes;
         tracing::debug!("GET request to {}", url);
         
         let response = self.http_client
             .get(&url)
-            .timeout(Duration::from_millis(100))
+            .timeout(self.request_timeout().await)
             .send()
-            .await?
-            .json()
             .await?;
+        let body = Self::read_body(response, max_response_bytes).await?;
             
-        Ok(response)
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    // Status only; the body is dropped unread
+    async fn get_status_code(&self, endpoint: &str) -> Result<u16, LimelightError> {
+        let url = self.build_url(endpoint).await?;
+        tracing::debug!("GET (probe) request to {}", url);
+
+        let response = self.http_client
+            .get(&url)
+            .timeout(self.request_timeout().await)
+            .send()
+            .await?;
+        Ok(response.status().as_u16())
+    }
+
+    async fn get_bytes(&self, endpoint: &str) -> Result<Vec<u8>, LimelightError> {
+        let url = self.build_url(endpoint).await?;
+        tracing::debug!("GET (binary) request to {}", url);
+
+        let response = self.http_client
+            .get(&url)
+            .timeout(self.request_timeout().await)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            tracing::error!("GET {} returned status {}", url, status);
+            return Err(LimelightError::UnexpectedStatus(status.as_u16()));
+        }
+
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn read_body(mut response: reqwest::Response, max_bytes: usize) -> Result<Vec<u8>, LimelightError> {
+        if let Some(length) = response.content_length() {
+            if length > max_bytes as u64 {
+                tracing::error!("Response Content-Length {} exceeds limit of {} bytes", length, max_bytes);
+                return Err(LimelightError::ResponseTooLarge(max_bytes));
+            }
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > max_bytes {
+                tracing::error!("Response body exceeded limit of {} bytes while streaming", max_bytes);
+                return Err(LimelightError::ResponseTooLarge(max_bytes));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
     }
 
     async fn post_json<T: serde::Serialize + ?Sized>(
@@ -175,28 +1303,100 @@ impl LimelightClient {
         endpoint: &str, 
         data: &T,
     ) -> Result<bool, LimelightError> {
-        let url = self.build_url(endpoint).await;
+        let url = self.build_url(endpoint).await?;
         tracing::debug!("POST request to {}", url);
         
-        let response = self.http_client
+        let request = self.http_client
             .post(&url)
             .json(data)
-            .timeout(Duration::from_millis(100))
-            .send()
-            .await?;
+            .timeout(self.request_timeout().await);
+        let response = self.send_command(request).await?;
             
         Ok(response.status().is_success())
     }
 
+    // Every state-changing request goes through here so it lands in the command history
+    async fn send_command(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, LimelightError> {
+        let request = request.build()?;
+        let history_len = self.config.read().await.command_history_len;
+        if history_len == 0 {
+            return Ok(self.http_client.execute(request).await?);
+        }
+
+        let method = request.method().to_string();
+        let url = request.url();
+        let endpoint = match url.query() {
+            Some(query) => format!("{}?{}", url.path().trim_start_matches('/'), query),
+            None => url.path().trim_start_matches('/').to_string(),
+        };
+        let timestamp = unix_seconds();
+        let response = self.http_client.execute(request).await;
+        let outcome = match &response {
+            Ok(response) => CommandOutcome::Status(response.status().as_u16()),
+            Err(e) => CommandOutcome::Failed(e.to_string()),
+        };
+
+        let mut history = self.command_history.write().await;
+        while history.len() >= history_len {
+            history.pop_front();
+        }
+        history.push_back(CommandRecord { timestamp, method, endpoint, outcome });
+        drop(history);
+
+        Ok(response?)
+    }
+
+    // Oldest first; empty unless command_history_len is set
+    pub async fn command_history(&self) -> Vec<CommandRecord> {
+        self.command_history.read().await.iter().cloned().collect()
+    }
+
+    // Pipeline JSON is sent compact, and gzipped when enabled. A device that rejects the
+    // encoding gets the plain body instead.
+    async fn post_pipeline_json(&self, endpoint: &str, data: &Value) -> Result<bool, LimelightError> {
+        let url = self.build_url(endpoint).await?;
+        let body = serde_json::to_vec(data)?;
+
+        if self.config.read().await.compress_pipeline_uploads {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            let compressed = encoder.write_all(&body)
+                .and_then(|_| encoder.finish())
+                .map_err(|e| LimelightError::ConfigError(format!("Failed to compress pipeline: {}", e)))?;
+            tracing::debug!("POST request to {} (gzip, {} -> {} bytes)", url, body.len(), compressed.len());
+
+            let request = self.http_client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(reqwest::header::CONTENT_ENCODING, "gzip")
+                .body(compressed)
+                .timeout(self.request_timeout().await);
+            let response = self.send_command(request).await?;
+            let status = response.status();
+            if status != reqwest::StatusCode::UNSUPPORTED_MEDIA_TYPE && status != reqwest::StatusCode::BAD_REQUEST {
+                return Ok(status.is_success());
+            }
+            tracing::warn!("Device rejected gzip pipeline upload with {}, retrying uncompressed", status);
+        }
+
+        tracing::debug!("POST request to {} ({} bytes)", url, body.len());
+        let request = self.http_client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .timeout(self.request_timeout().await);
+        let response = self.send_command(request).await?;
+
+        Ok(response.status().is_success())
+    }
+
     async fn delete(&self, endpoint: &str) -> Result<bool, LimelightError> {
-        let url = self.build_url(endpoint).await;
+        let url = self.build_url(endpoint).await?;
         tracing::debug!("DELETE request to {}", url);
         
-        let response = self.http_client
+        let request = self.http_client
             .delete(&url)
-            .timeout(Duration::from_millis(100))
-            .send()
-            .await?;
+            .timeout(self.request_timeout().await);
+        let response = self.send_command(request).await?;
             
         Ok(response.status().is_success())
     }
@@ -211,14 +1411,23 @@ impl LimelightClient {
         result
     }
 
-    async fn fetch_results(client: &HttpClient, base_url: &str) -> Result<LimelightResult, LimelightError> {
+    async fn fetch_results(client: &HttpClient, base_url: &str, limits: FetchLimits) -> Result<LimelightResult, LimelightError> {
+        Self::fetch_result_batch(client, base_url, limits)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| LimelightError::UnexpectedResponse("Device returned an empty results array".into()))
+    }
+
+    // Some firmware modes answer /results with a top-level array instead of a single object
+    async fn fetch_result_batch(client: &HttpClient, base_url: &str, limits: FetchLimits) -> Result<Vec<LimelightResult>, LimelightError> {
         let url = format!("{}/results", base_url);
         tracing::debug!("Fetching results from: {}", url);
 
         tracing::debug!("Sending HTTP GET request");
         let response = client
             .get(&url)
-            .timeout(Duration::from_millis(100))
+            .timeout(limits.request_timeout)
             .send()
             .await;
         
@@ -226,24 +1435,17 @@ impl LimelightClient {
             Ok(resp) => {
                 let status = resp.status();
                 tracing::debug!("Got HTTP response with status: {}", status);
+                if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    let retry_after = retry_after(&resp);
+                    tracing::warn!("Device rate limited results request, retry after {:?}", retry_after);
+                    return Err(LimelightError::RateLimited(retry_after));
+                }
                 
                 tracing::debug!("Reading response body");
-                let text = resp.text().await?;
+                let body = Self::read_body(resp, limits.max_response_bytes).await?;
+                let text = String::from_utf8_lossy(&body);
                 tracing::debug!("Raw JSON response (length={}): {}", text.len(), text);
-                
-                tracing::debug!("Attempting to parse JSON");
-                match serde_json::from_str::<LimelightResult>(&text) {
-                    Ok(result) => {
-                        tracing::debug!("Successfully parsed JSON response");
-                        tracing::trace!("Parsed result: {:?}", result);
-                        Ok(result)
-                    }
-                    Err(e) => {
-                        tracing::error!("JSON parsing error: {:?}", e);
-                        tracing::error!("Failed JSON content: {}", text);
-                        Err(LimelightError::JsonError(e))
-                    }
-                }
+                Self::parse_result_batch(&text, limits)
             }
             Err(e) => {
                 tracing::error!("HTTP request failed: {:?}", e);
@@ -252,22 +1454,117 @@ impl LimelightClient {
         }
     }
 
+    // Shared by HTTP polling and WebSocket frames
+    fn parse_result_batch(text: &str, limits: FetchLimits) -> Result<Vec<LimelightResult>, LimelightError> {
+        tracing::debug!("Attempting to parse JSON");
+        let parsed = if text.trim_start().starts_with('[') {
+            serde_json::from_str::<Vec<LimelightResult>>(text)
+        } else {
+            serde_json::from_str::<LimelightResult>(text).map(|result| vec![result])
+        };
+        match parsed {
+            Ok(mut results) => {
+                tracing::debug!("Successfully parsed JSON response with {} results", results.len());
+                for result in &mut results {
+                    let dropped = result.truncate_targets(limits.max_targets_per_type);
+                    if dropped > 0 {
+                        tracing::warn!("Dropped {} targets beyond max_targets_per_type={}", dropped, limits.max_targets_per_type);
+                    }
+                }
+                tracing::trace!("Parsed results: {:?}", results);
+                Ok(results)
+            }
+            Err(e) => {
+                tracing::error!("JSON parsing error: {:?}", e);
+                tracing::error!("Failed JSON content: {}", text);
+                Err(LimelightError::JsonError(e))
+            }
+        }
+    }
+
     pub async fn get_status(&self) -> Result<Value, LimelightError> {
         self.get_json("status").await
     }
 
+    pub async fn get_status_typed(&self) -> Result<LimelightStatus, LimelightError> {
+        self.get_json("status").await
+    }
+
     pub async fn reload_pipeline(&self) -> Result<bool, LimelightError> {
         self.post_json("reload-pipeline", &()).await
     }
 
+    // Reloads, then polls /results directly until two successive responses parse and `ts`
+    // advances between them. Works whether or not the poll loop is running.
+    pub async fn reload_pipeline_and_wait(&self, timeout: Duration) -> Result<bool, LimelightError> {
+        if !self.reload_pipeline().await? {
+            return Ok(false);
+        }
+
+        let limits = FetchLimits::from(&*self.config.read().await);
+        tokio::time::timeout(timeout, async {
+            let mut last_ts = None;
+            loop {
+                tokio::time::sleep(RELOAD_POLL_INTERVAL).await;
+                let base_url = match self.base_url().await {
+                    Ok(base_url) => base_url,
+                    Err(_) => continue,
+                };
+                match Self::fetch_results(&self.http_client, &base_url, limits).await {
+                    Ok(result) => match (last_ts, result.ts) {
+                        (Some(prev), Some(ts)) if ts > prev => {
+                            tracing::debug!("Results resumed after pipeline reload");
+                            return Ok(true);
+                        }
+                        (_, ts) => last_ts = ts,
+                    },
+                    Err(e) => {
+                        tracing::debug!("Results not yet available after reload: {:?}", e);
+                        last_ts = None;
+                    }
+                }
+            }
+        })
+        .await
+        .map_err(|_| LimelightError::TimeoutError)?
+    }
+
+    // Rejects a pipeline whose type the device reports as unsupported. The pipeline is only
+    // read back when the device lists its supported types.
     pub async fn switch_pipeline(&self, index: u32) -> Result<bool, LimelightError> {
+        if self.supported_pipeline_types_cached().await.is_some_and(|supported| !supported.is_empty()) {
+            match self.get_pipeline_at_index(index).await {
+                Ok(pipeline) => {
+                    if let Some(pipeline_type) = pipeline.get("pipeline_type").and_then(Value::as_str) {
+                        self.check_pipeline_type_supported(&PipelineType::from(pipeline_type)).await?;
+                    }
+                }
+                Err(e) => tracing::debug!("Skipping pipeline type check, pipeline {} unavailable: {:?}", index, e),
+            }
+        }
         self.post_json(&format!("pipeline-switch?index={}", index), &()).await
     }
 
+    // Only switches when `index` isn't already active; returns whether a switch happened
+    pub async fn ensure_pipeline(&self, index: u32) -> Result<bool, LimelightError> {
+        let active = self.active_pipeline_index().await?;
+        if active == index {
+            tracing::debug!("Pipeline {} already active, skipping switch", index);
+            return Ok(false);
+        }
+        tracing::debug!("Switching pipeline from {} to {}", active, index);
+        self.switch_pipeline(index).await
+    }
+
     pub async fn capture_snapshot(&self, snapname: &str) -> Result<bool, LimelightError> {
         self.post_json(&format!("capture-snapshot?snapname={}", snapname), &()).await
     }
 
+    // Returns the current processed frame without storing a named snapshot on the device
+    pub async fn grab_frame(&self) -> Result<Vec<u8>, LimelightError> {
+        self.get_bytes("capture-frame").await
+    }
+
     pub async fn delete_snapshots(&self) -> Result<bool, LimelightError> {
         self.delete("delete-snapshots").await
     }
@@ -277,16 +1574,47 @@ impl LimelightClient {
     }
 
     pub async fn update_python_inputs(&self, inputs: &[f64]) -> Result<bool, LimelightError> {
-        const MAX_INPUTS: usize = 32;
-        if inputs.is_empty() || inputs.len() > MAX_INPUTS {
-            return Err(LimelightError::ConfigError("Invalid number of Python inputs".into()));
-        }
+        validate_python_inputs(inputs)?;
         self.post_json("update-pythoninputs", inputs).await
     }
 
+    // Yaw only; rates, pitch and roll are sent as zero
     pub async fn update_robot_orientation(&self, yaw: f64) -> Result<bool, LimelightError> {
-        let orientation_data = vec![yaw, 0.0, 0.0, 0.0, 0.0, 0.0];
-        self.post_json("update-robotorientation", &orientation_data).await
+        self.update_robot_orientation_full(yaw, 0.0, 0.0, 0.0, 0.0, 0.0).await
+    }
+
+    // Degrees and degrees per second, posted in the camera's [yaw, yawRate, pitch, pitchRate,
+    // roll, rollRate] order
+    pub async fn update_robot_orientation_full(
+        &self,
+        yaw: f64,
+        yaw_rate: f64,
+        pitch: f64,
+        pitch_rate: f64,
+        roll: f64,
+        roll_rate: f64,
+    ) -> Result<bool, LimelightError> {
+        let orientation_data = [yaw, yaw_rate, pitch, pitch_rate, roll, roll_rate];
+        let accepted = self.post_json("update-robotorientation", &orientation_data).await?;
+        if accepted {
+            *self.orientation_updated_at.write().await = Some(Instant::now());
+        }
+        Ok(accepted)
+    }
+
+    // Clears whatever orientation the camera holds by posting all zeros. Unlike
+    // update_robot_orientation(0.0) this is not a heading, so MT2 freshness is reset too.
+    pub async fn reset_robot_orientation(&self) -> Result<bool, LimelightError> {
+        let accepted = self.post_json("update-robotorientation", &[0.0; 6]).await?;
+        if accepted {
+            *self.orientation_updated_at.write().await = None;
+        }
+        Ok(accepted)
+    }
+
+    // MegaTag2 poses are only meaningful while the device holds a recent robot orientation
+    pub async fn megatag2_orientation_fresh(&self, max_age: Duration) -> bool {
+        self.orientation_updated_at.read().await.is_some_and(|at| at.elapsed() <= max_age)
     }
 
     pub async fn upload_field_map(&self, field_map: Value, index: Option<u32>) -> Result<bool, LimelightError> {
@@ -297,6 +1625,18 @@ impl LimelightClient {
         self.post_json(&endpoint, &field_map).await
     }
 
+    pub async fn get_field_map(&self, index: Option<u32>) -> Result<Value, LimelightError> {
+        let endpoint = match index {
+            Some(idx) => format!("get-fieldmap?index={}", idx),
+            None => "get-fieldmap".to_string(),
+        };
+        self.get_json(&endpoint).await
+    }
+
+    pub async fn field_map(&self, index: Option<u32>) -> Result<FieldMap, LimelightError> {
+        Ok(serde_json::from_value(self.get_field_map(index).await?)?)
+    }
+
     pub async fn get_calibration(&self, source: &str) -> Result<Value, LimelightError> {
         self.get_json(&format!("cal-{}", source)).await
     }
@@ -305,6 +1645,108 @@ impl LimelightClient {
         self.get_json("hwreport").await
     }
 
+    pub async fn get_hardware_report_typed(&self) -> Result<HardwareReport, LimelightError> {
+        self.get_json("hwreport").await
+    }
+
+    // From the hardware report's "pipelinetypes" list; empty when the firmware doesn't report it
+    pub async fn get_supported_pipeline_types(&self) -> Result<Vec<PipelineType>, LimelightError> {
+        let report = self.get_hardware_report().await?;
+        let types = report.get("pipelinetypes")
+            .and_then(Value::as_array)
+            .map(|types| types.iter().filter_map(Value::as_str).map(PipelineType::from).collect())
+            .unwrap_or_default();
+        tracing::debug!("Supported pipeline types: {:?}", types);
+        Ok(types)
+    }
+
+    // Fetched once per client; None while the hardware report can't be fetched or parsed
+    async fn supported_pipeline_types_cached(&self) -> Option<Vec<PipelineType>> {
+        if let Some(supported) = self.supported_pipeline_types.read().await.clone() {
+            return Some(supported);
+        }
+        match self.get_supported_pipeline_types().await {
+            Ok(supported) => {
+                *self.supported_pipeline_types.write().await = Some(supported.clone());
+                Some(supported)
+            }
+            Err(e) => {
+                tracing::debug!("Hardware report unavailable: {:?}", e);
+                None
+            }
+        }
+    }
+
+    // Errors only when the device lists its types and `pipeline_type` isn't among them. A
+    // hardware report that can't be fetched or parsed skips the check.
+    async fn check_pipeline_type_supported(&self, pipeline_type: &PipelineType) -> Result<(), LimelightError> {
+        let Some(supported) = self.supported_pipeline_types_cached().await else {
+            tracing::debug!("Skipping pipeline type check for {}", pipeline_type);
+            return Ok(());
+        };
+        if !supported.is_empty() && !supported.contains(pipeline_type) {
+            return Err(LimelightError::ConfigError(format!(
+                "Pipeline type {} is not supported by this device", pipeline_type
+            )));
+        }
+        Ok(())
+    }
+
+    pub async fn get_firmware_info(&self) -> Result<FirmwareInfo, LimelightError> {
+        let report = self.get_hardware_report().await?;
+        let info = FirmwareInfo::from_hardware_report(&report);
+        tracing::debug!("Firmware info: {:?}", info);
+        Ok(info)
+    }
+
+    // Issues a GET to each read-only endpoint in PROBED_ENDPOINTS. Endpoints that error or
+    // return a non-2xx status are recorded as unsupported.
+    pub async fn probe_endpoints(&self) -> EndpointSupport {
+        let mut support = EndpointSupport::default();
+        for endpoint in PROBED_ENDPOINTS {
+            let supported = match self.get_status_code(endpoint).await {
+                Ok(status) => (200..300).contains(&status),
+                Err(e) => {
+                    tracing::debug!("Probe of {} failed: {:?}", endpoint, e);
+                    false
+                }
+            };
+            tracing::debug!("Endpoint {} supported: {}", endpoint, supported);
+            support.endpoints.insert(endpoint.to_string(), supported);
+        }
+        support
+    }
+
+    // Network Identity
+    pub async fn get_hostname(&self) -> Result<String, LimelightError> {
+        self.get_json("hostname").await
+    }
+
+    pub async fn set_hostname(&self, hostname: &str) -> Result<bool, LimelightError> {
+        let valid = !hostname.is_empty()
+            && hostname.len() <= 63
+            && !hostname.starts_with('-')
+            && !hostname.ends_with('-')
+            && hostname.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !valid {
+            return Err(LimelightError::ConfigError(format!(
+                "Invalid hostname '{}': use 1-63 letters, digits or hyphens, not starting or ending with a hyphen", hostname
+            )));
+        }
+        self.post_json(&format!("update-hostname?hostname={}", hostname), &()).await
+    }
+
+    pub async fn set_team_number(&self, team: u16) -> Result<bool, LimelightError> {
+        // The team number maps to 10.TE.AM.x, so TE must fit in an octet
+        const MAX_TEAM: u16 = 25599;
+        if team == 0 || team > MAX_TEAM {
+            return Err(LimelightError::ConfigError(format!(
+                "Team number must be in 1..={}, got {}", MAX_TEAM, team
+            )));
+        }
+        self.post_json(&format!("update-teamnumber?team={}", team), &()).await
+    }
+
     // Pipeline Management
     pub async fn get_default_pipeline(&self) -> Result<Value, LimelightError> {
         self.get_json("pipeline-default").await
@@ -315,15 +1757,113 @@ impl LimelightClient {
     }
 
     pub async fn update_pipeline(&self, settings: Value, flush: bool) -> Result<bool, LimelightError> {
-        self.post_json(&format!("update-pipeline?flush={}", if flush { 1 } else { 0 }), &settings).await
+        self.post_pipeline_json(&format!("update-pipeline?flush={}", if flush { 1 } else { 0 }), &settings).await
+    }
+
+    // Fetches the active pipeline, applies `f`, and writes the whole pipeline back with flush
+    pub async fn modify_pipeline(&self, f: impl FnOnce(&mut Value)) -> Result<bool, LimelightError> {
+        let index = self.active_pipeline_index().await?;
+        tracing::debug!("Modifying active pipeline {}", index);
+        let mut pipeline = self.get_pipeline_at_index(index).await?;
+        f(&mut pipeline);
+        self.update_pipeline(pipeline, true).await
+    }
+
+    async fn active_pipeline_index(&self) -> Result<u32, LimelightError> {
+        let cached = if *self.running.read().await {
+            self.get_latest_result().await
+        } else {
+            None
+        };
+        let result = match cached {
+            Some(result) => result,
+            None => self.fetch_once().await?,
+        };
+        result.pipeline_id
+            .and_then(|id| u32::try_from(id).ok())
+            .ok_or_else(|| LimelightError::UnexpectedResponse("Device did not report an active pipeline index".into()))
+    }
+
+    pub async fn set_exposure(&self, microseconds: f64, flush: bool) -> Result<bool, LimelightError> {
+        const MAX_EXPOSURE_US: f64 = 33_000.0;
+        if !(microseconds > 0.0 && microseconds <= MAX_EXPOSURE_US) {
+            return Err(LimelightError::ConfigError(format!(
+                "Exposure must be in (0, {}] microseconds, got {}", MAX_EXPOSURE_US, microseconds
+            )));
+        }
+        self.update_pipeline(json!({ "exposure": microseconds }), flush).await
+    }
+
+    pub async fn set_gain(&self, gain: f64, flush: bool) -> Result<bool, LimelightError> {
+        const MAX_GAIN: f64 = 100.0;
+        if !(0.0..=MAX_GAIN).contains(&gain) {
+            return Err(LimelightError::ConfigError(format!(
+                "Sensor gain must be in [0, {}], got {}", MAX_GAIN, gain
+            )));
+        }
+        self.update_pipeline(json!({ "sensor_gain": gain }), flush).await
+    }
+
+    // Detection runs on the image downscaled by this factor: more range at 1, more fps at 4
+    pub async fn set_apriltag_decimation(&self, value: u32, flush: bool) -> Result<bool, LimelightError> {
+        const SUPPORTED: [u32; 4] = [1, 2, 3, 4];
+        if !SUPPORTED.contains(&value) {
+            return Err(LimelightError::ConfigError(format!(
+                "AprilTag decimation must be one of {:?}, got {}", SUPPORTED, value
+            )));
+        }
+        self.update_pipeline(json!({ "fiducial_resdiv": value }), flush).await
+    }
+
+    // Sent as the NetworkTables-style "ledMode" pipeline setting, not flushed
+    pub async fn set_led_mode(&self, mode: LedMode) -> Result<bool, LimelightError> {
+        tracing::debug!("Setting LED mode to {:?}", mode);
+        self.update_pipeline(json!({ "ledMode": mode.value() }), false).await
+    }
+
+    // Sent as the NetworkTables-style "stream" pipeline setting, not flushed
+    pub async fn set_stream_mode(&self, mode: StreamMode) -> Result<bool, LimelightError> {
+        tracing::debug!("Setting stream mode to {:?}", mode);
+        self.update_pipeline(json!({ "stream": mode.value() }), false).await
+    }
+
+    // Limits processing to a window in normalized image coordinates (-1 to 1 on each axis).
+    // Values outside that range are clamped first; NaN bounds, and windows that are inverted or
+    // empty once clamped, are rejected. Not flushed, since crop is usually tuned live.
+    pub async fn set_crop(&self, x_min: f64, x_max: f64, y_min: f64, y_max: f64) -> Result<bool, LimelightError> {
+        let bounds = [x_min, x_max, y_min, y_max];
+        if bounds.iter().any(|v| v.is_nan()) {
+            return Err(LimelightError::ConfigError(format!("Crop bounds must be numbers, got {:?}", bounds)));
+        }
+        let [x_min, x_max, y_min, y_max] = bounds.map(|v| v.clamp(-1.0, 1.0));
+        if x_min >= x_max || y_min >= y_max {
+            return Err(LimelightError::ConfigError(format!(
+                "Crop window is inverted or empty after clamping: x {}..{}, y {}..{}", x_min, x_max, y_min, y_max
+            )));
+        }
+        self.update_pipeline(
+            json!({ "crop_x_min": x_min, "crop_x_max": x_max, "crop_y_min": y_min, "crop_y_max": y_max }),
+            false,
+        )
+        .await
+    }
+
+    // None if the active pipeline doesn't carry the setting
+    pub async fn get_apriltag_decimation(&self) -> Result<Option<u32>, LimelightError> {
+        let index = self.active_pipeline_index().await?;
+        let pipeline = self.get_pipeline_at_index(index).await?;
+        Ok(pipeline.get("fiducial_resdiv").and_then(Value::as_f64).map(|v| v.round() as u32))
     }
 
     pub async fn upload_pipeline(&self, pipeline: Value, index: Option<u32>) -> Result<bool, LimelightError> {
+        if let Some(pipeline_type) = pipeline.get("pipeline_type").and_then(Value::as_str) {
+            self.check_pipeline_type_supported(&PipelineType::from(pipeline_type)).await?;
+        }
         let endpoint = match index {
             Some(idx) => format!("upload-pipeline?index={}", idx),
             None => "upload-pipeline".to_string(),
         };
-        self.post_json(&endpoint, &pipeline).await
+        self.post_pipeline_json(&endpoint, &pipeline).await
     }
 
     // Neural Network Management
@@ -335,18 +1875,44 @@ impl LimelightClient {
             Some(idx) => format!("upload-nn?type={}&index={}", nn_type, idx),
             None => format!("upload-nn?type={}", nn_type),
         };
-        let url = self.build_url(&endpoint).await;
+        let url = self.build_url(&endpoint).await?;
         
-        let response = self.http_client
+        let request = self.http_client
             .post(&url)
             .body(data.to_vec())
-            .timeout(Duration::from_millis(100))
-            .send()
-            .await?;
+            .timeout(self.upload_timeout().await);
+        let response = self.send_command(request).await?;
             
         Ok(response.status().is_success())
     }
 
+    // Uploads, then compares the size the device reports for the model against the bytes sent
+    pub async fn upload_neural_network_verified(&self, nn_type: &str, data: &[u8], index: Option<u32>) -> Result<bool, LimelightError> {
+        if !self.upload_neural_network(nn_type, data, index).await? {
+            return Ok(false);
+        }
+
+        let info = self.get_neural_network_info(nn_type, index).await?;
+        match info.size {
+            Some(size) if size == data.len() as u64 => {
+                tracing::debug!("Verified {} byte {} upload", size, nn_type);
+                Ok(true)
+            }
+            Some(size) => Err(LimelightError::UploadVerificationFailed(format!(
+                "device reports {} bytes, uploaded {}", size, data.len()
+            ))),
+            None => Err(LimelightError::UploadVerificationFailed("device did not report a model size".into())),
+        }
+    }
+
+    pub async fn get_neural_network_info(&self, nn_type: &str, index: Option<u32>) -> Result<NeuralNetworkInfo, LimelightError> {
+        let endpoint = match index {
+            Some(idx) => format!("nn-info?type={}&index={}", nn_type, idx),
+            None => format!("nn-info?type={}", nn_type),
+        };
+        self.get_json(&endpoint).await
+    }
+
     pub async fn upload_neural_network_labels(&self, nn_type: &str, labels: &str, index: Option<u32>) -> Result<bool, LimelightError> {
         if !["detector", "classifier"].contains(&nn_type) {
             return Err(LimelightError::ConfigError("Invalid neural network type".into()));
@@ -355,23 +1921,35 @@ impl LimelightClient {
             Some(idx) => format!("upload-nnlabels?type={}&index={}", nn_type, idx),
             None => format!("upload-nnlabels?type={}", nn_type),
         };
-        let url = self.build_url(&endpoint).await;
+        let url = self.build_url(&endpoint).await?;
         
-        let response = self.http_client
+        let request = self.http_client
             .post(&url)
             .body(labels.to_string())
-            .timeout(Duration::from_millis(100))
-            .send()
-            .await?;
+            .timeout(self.upload_timeout().await);
+        let response = self.send_command(request).await?;
             
         Ok(response.status().is_success())
     }
 
+    pub async fn get_neural_network_models(&self) -> Result<Vec<String>, LimelightError> {
+        self.get_json("getnnmodelnames").await
+    }
+
     // SnapScript Management
     pub async fn get_snapscript_names(&self) -> Result<Vec<String>, LimelightError> {
         self.get_json("getsnapsscriptnames").await
     }
 
+    // Number of values the active SnapScript writes to python_out
+    pub async fn get_python_output_count(&self) -> Result<usize, LimelightError> {
+        let status = self.get_status().await?;
+        status.get("pythonOutputCount")
+            .and_then(Value::as_u64)
+            .map(|count| count as usize)
+            .ok_or_else(|| LimelightError::UnexpectedResponse("Device status does not report a Python output count".into()))
+    }
+
     // Calibration Management
     pub async fn get_calibration_default(&self) -> Result<Value, LimelightError> {
         self.get_json("cal-default").await
@@ -389,6 +1967,82 @@ impl LimelightClient {
         self.get_json("cal-latest").await
     }
 
+    // The most recent calibration run, with its quality metrics, before it's saved anywhere
+    pub async fn latest_calibration_result(&self) -> Result<CameraCalibration, LimelightError> {
+        self.get_json("cal-latest").await
+    }
+
+    // Cached calibration in use (file, then eeprom, then default); refetched when the pipeline changes
+    pub async fn calibration(&self) -> Result<CameraCalibration, LimelightError> {
+        let pipeline_id = self.latest_result.read().await.as_ref().and_then(|r| r.pipeline_id);
+        if let Some(cached) = self.calibration_cache.read().await.as_ref() {
+            if cached.pipeline_id == pipeline_id {
+                return Ok(cached.calibration.clone());
+            }
+            tracing::debug!("Pipeline changed from {:?} to {:?}, invalidating calibration cache", cached.pipeline_id, pipeline_id);
+        }
+        self.refresh_calibration().await
+    }
+
+    pub async fn refresh_calibration(&self) -> Result<CameraCalibration, LimelightError> {
+        let pipeline_id = self.latest_result.read().await.as_ref().and_then(|r| r.pipeline_id);
+        let mut last_error = None;
+        for source in ["cal-file", "cal-eeprom", "cal-default"] {
+            match self.get_json::<CameraCalibration>(source).await {
+                Ok(calibration) if calibration.is_valid() => {
+                    tracing::debug!("Caching calibration from {}", source);
+                    *self.calibration_cache.write().await = Some(CachedCalibration { pipeline_id, calibration: calibration.clone() });
+                    return Ok(calibration);
+                }
+                Ok(_) => tracing::debug!("No usable calibration in {}", source),
+                Err(e) => {
+                    tracing::debug!("Failed to fetch {}: {:?}", source, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| LimelightError::ConfigError("Device has no usable calibration".into())))
+    }
+
+    // True when cal-file or cal-eeprom holds valid intrinsics that differ from cal-default.
+    // A source that's missing or unparseable counts as uncalibrated; connection failures are errors.
+    pub async fn is_calibrated(&self) -> Result<bool, LimelightError> {
+        let default = match self.get_json::<CameraCalibration>("cal-default").await {
+            Ok(calibration) => calibration.intrinsics,
+            Err(e @ (LimelightError::HttpError(_) | LimelightError::TimeoutError)) => return Err(e),
+            Err(_) => None,
+        };
+        for source in ["cal-file", "cal-eeprom"] {
+            match self.get_json::<CameraCalibration>(source).await {
+                Ok(calibration) if calibration.is_valid() && calibration.intrinsics != default => {
+                    tracing::debug!("Device calibrated via {}", source);
+                    return Ok(true);
+                }
+                Ok(_) => tracing::debug!("{} holds no custom calibration", source),
+                Err(e @ (LimelightError::HttpError(_) | LimelightError::TimeoutError)) => return Err(e),
+                Err(e) => tracing::debug!("Failed to read {}: {:?}", source, e),
+            }
+        }
+        Ok(false)
+    }
+
+    // Rechecks is_calibrated until it passes; errors while waiting (e.g. the device is still
+    // booting) are retried rather than returned
+    pub async fn wait_for_calibration(&self, timeout: Duration) -> Result<(), LimelightError> {
+        tokio::time::timeout(timeout, async {
+            loop {
+                match self.is_calibrated().await {
+                    Ok(true) => return,
+                    Ok(false) => tracing::debug!("Waiting for calibration"),
+                    Err(e) => tracing::debug!("Calibration check failed: {:?}", e),
+                }
+                tokio::time::sleep(CALIBRATION_POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .map_err(|_| LimelightError::TimeoutError)
+    }
+
     pub async fn update_calibration_eeprom(&self, calibration: Value) -> Result<bool, LimelightError> {
         self.post_json("cal-eeprom", &calibration).await
     }
@@ -411,14 +2065,13 @@ impl LimelightClient {
 
     // Snapshot Management
     pub async fn upload_snapshot(&self, snapname: &str, image_data: &[u8]) -> Result<bool, LimelightError> {
-        let url = self.build_url(&format!("upload-snapshot?snapname={}", snapname)).await;
+        let url = self.build_url(&format!("upload-snapshot?snapname={}", snapname)).await?;
         
-        let response = self.http_client
+        let request = self.http_client
             .post(&url)
             .body(image_data.to_vec())
-            .timeout(Duration::from_millis(100))
-            .send()
-            .await?;
+            .timeout(self.upload_timeout().await);
+        let response = self.send_command(request).await?;
             
         Ok(response.status().is_success())
     }
@@ -426,4 +2079,105 @@ impl LimelightClient {
     pub async fn get_snapshot_manifest(&self) -> Result<Vec<String>, LimelightError> {
         self.get_json("snapshotmanifest").await
     }
-}
\ No newline at end of file
+
+    // Raw image bytes of a stored snapshot
+    pub async fn download_snapshot(&self, snapname: &str) -> Result<Vec<u8>, LimelightError> {
+        match self.get_bytes(&format!("snapshot?snapname={}", snapname)).await {
+            Err(LimelightError::UnexpectedStatus(404)) => Err(LimelightError::SnapshotNotFound(snapname.to_string())),
+            other => other,
+        }
+    }
+
+    // Background task that polls the manifest every `poll`, downloads snapshots not yet in
+    // `dir`, and optionally deletes them from the device once written. A failed download or
+    // write is logged and retried on the next pass.
+    pub async fn auto_archive_snapshots(
+        &self,
+        dir: PathBuf,
+        poll: Duration,
+        delete_from_device: bool,
+    ) -> Result<SnapshotArchiveHandle, LimelightError> {
+        if poll.is_zero() {
+            return Err(LimelightError::ConfigError("Snapshot archive poll interval cannot be zero".into()));
+        }
+        tokio::fs::create_dir_all(&dir).await?;
+        tracing::debug!("Archiving snapshots to {:?} every {:?}", dir, poll);
+        Ok(archive::spawn(self.clone(), dir, poll, delete_from_device))
+    }
+}
+
+// serde_json would send NaN and infinities as null, which the SnapScript can't tell apart
+// from a missing input
+fn validate_python_inputs(inputs: &[f64]) -> Result<(), LimelightError> {
+    const MAX_INPUTS: usize = 32;
+    if inputs.is_empty() || inputs.len() > MAX_INPUTS {
+        return Err(LimelightError::ConfigError(format!(
+            "Python inputs must be 1..={}, got {}", MAX_INPUTS, inputs.len()
+        )));
+    }
+    if let Some((index, value)) = inputs.iter().enumerate().find(|(_, v)| !v.is_finite()) {
+        return Err(LimelightError::ConfigError(format!(
+            "Python input {} is {}, inputs must be finite", index, value
+        )));
+    }
+    Ok(())
+}
+
+// Extra delay on top of the regular tick so the effective period doubles with every
+// consecutive failure, topping out at `max_backoff_ms`
+fn backoff_pause(interval_ms: u64, max_backoff_ms: u64, failures: u32) -> Duration {
+    let period_ms = interval_ms.saturating_mul(1u64 << failures.min(32)).min(max_backoff_ms);
+    Duration::from_millis(period_ms.saturating_sub(interval_ms))
+}
+
+fn target_summary(result: &LimelightResult) -> Value {
+    json!({
+        "timestamp": unix_seconds(),
+        "pipeline_id": result.pipeline_id,
+        "tx": result.tx,
+        "ty": result.ty,
+        "ta": result.ta,
+        "tag_ids": result.fiducial.iter().filter_map(|f| f.f_id).collect::<Vec<_>>(),
+    })
+}
+
+// Only the delay-seconds form is understood; HTTP-date values fall back to the default pause
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+// Headers and user agent are baked into the client so every request carries them.
+// Entries that aren't valid HTTP headers are skipped with a warning.
+fn build_http_client(config: &LimelightConfig) -> HttpClient {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &config.headers {
+        match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+            (Ok(name), Ok(value)) => {
+                headers.insert(name, value);
+            }
+            _ => tracing::warn!("Ignoring invalid header {:?}", name),
+        }
+    }
+
+    if let Some(user_agent) = &config.user_agent {
+        match HeaderValue::from_str(user_agent) {
+            Ok(value) => {
+                headers.insert(USER_AGENT, value);
+            }
+            Err(_) => tracing::warn!("Ignoring invalid user agent {:?}", user_agent),
+        }
+    }
+
+    HttpClient::builder().default_headers(headers).build().unwrap_or_else(|e| {
+        tracing::warn!("Failed to build HTTP client with custom headers: {:?}", e);
+        HttpClient::new()
+    })
+}
+
+pub(crate) fn unix_seconds() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
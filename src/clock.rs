@@ -0,0 +1,110 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::time::{interval, sleep, Duration, Interval};
+
+pub type TickFuture<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+// Drives the poll loop cadence. A new ticker is created whenever the poll rate changes.
+pub trait Clock: Send + Sync + 'static {
+    fn ticker(&self, period: Duration) -> Box<dyn Ticker>;
+}
+
+pub trait Ticker: Send {
+    fn tick(&mut self) -> TickFuture<'_>;
+}
+
+// Default clock backed by tokio::time::interval; the first tick completes immediately
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IntervalClock;
+
+impl Clock for IntervalClock {
+    fn ticker(&self, period: Duration) -> Box<dyn Ticker> {
+        Box::new(IntervalTicker(interval(period)))
+    }
+}
+
+struct IntervalTicker(Interval);
+
+impl Ticker for IntervalTicker {
+    fn tick(&mut self) -> TickFuture<'_> {
+        Box::pin(async move {
+            self.0.tick().await;
+        })
+    }
+}
+
+// Plain sleep between ticks, for targets where tokio's interval misbehaves (e.g. WASM)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SleepClock;
+
+impl Clock for SleepClock {
+    fn ticker(&self, period: Duration) -> Box<dyn Ticker> {
+        Box::new(SleepTicker { period, first: true })
+    }
+}
+
+struct SleepTicker {
+    period: Duration,
+    first: bool,
+}
+
+impl Ticker for SleepTicker {
+    fn tick(&mut self) -> TickFuture<'_> {
+        Box::pin(async move {
+            if self.first {
+                self.first = false;
+                return;
+            }
+            sleep(self.period).await;
+        })
+    }
+}
+
+// Deterministic clock for tests: each call to advance() releases that many ticks
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    ticks: Arc<Semaphore>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            ticks: Arc::new(Semaphore::new(0)),
+        }
+    }
+
+    pub fn advance(&self, ticks: usize) {
+        tracing::debug!("Advancing manual clock by {} ticks", ticks);
+        self.ticks.add_permits(ticks);
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn ticker(&self, _period: Duration) -> Box<dyn Ticker> {
+        Box::new(ManualTicker {
+            ticks: self.ticks.clone(),
+        })
+    }
+}
+
+struct ManualTicker {
+    ticks: Arc<Semaphore>,
+}
+
+impl Ticker for ManualTicker {
+    fn tick(&mut self) -> TickFuture<'_> {
+        Box::pin(async move {
+            if let Ok(permit) = self.ticks.acquire().await {
+                permit.forget();
+            }
+        })
+    }
+}
@@ -3,7 +3,7 @@ use thiserror::Error;
 #[derive(Error, Debug)]
 pub enum LimelightError {
     #[error("HTTP request failed: {0}")]
-    HttpError(#[from] reqwest::Error),
+    HttpError(reqwest::Error),
     
     #[error("WebSocket error: {0}")]
     WebSocketError(#[from] tokio_tungstenite::tungstenite::Error),
@@ -11,15 +11,53 @@ pub enum LimelightError {
     #[error("JSON parsing error: {0}")]
     JsonError(#[from] serde_json::Error),
     
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    
     #[error("Invalid URL: {0}")]
     UrlError(#[from] url::ParseError),
     
+    #[error("Background task failed: {0}")]
+    TaskFailed(#[from] tokio::task::JoinError),
+    
     #[error("Configuration error: {0}")]
     ConfigError(String),
     
+    #[error("Unexpected HTTP status: {0}")]
+    UnexpectedStatus(u16),
+    
+    #[error("Unexpected device response: {0}")]
+    UnexpectedResponse(String),
+    
+    #[error("Rate limited by device (retry after {0:?})")]
+    RateLimited(Option<std::time::Duration>),
+    
+    #[error("Response body exceeded {0} bytes")]
+    ResponseTooLarge(usize),
+    
+    #[error("Upload verification failed: {0}")]
+    UploadVerificationFailed(String),
+    
+    #[error("Snapshot not found: {0}")]
+    SnapshotNotFound(String),
+    
+    #[error("Pose samples too inconsistent: {0}")]
+    InconsistentPose(String),
+    
     #[error("Connection timeout")]
     TimeoutError,
     
     #[error("Client not running")]
     NotRunning,
+}
+
+// Timeouts get their own variant so callers can tell them apart from refused connections
+impl From<reqwest::Error> for LimelightError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            LimelightError::TimeoutError
+        } else {
+            LimelightError::HttpError(error)
+        }
+    }
 }
\ No newline at end of file
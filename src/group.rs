@@ -0,0 +1,68 @@
+use crate::pool::merge_subscriptions;
+use crate::{LimelightClient, LimelightError, LimelightResult};
+use tokio::sync::broadcast;
+
+// Several cameras managed together under user-chosen labels, without the pool's mount
+// offsets or target fusion
+#[derive(Default)]
+pub struct LimelightGroup {
+    cameras: Vec<(String, LimelightClient)>,
+}
+
+impl LimelightGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Replaces any camera already registered under the same label
+    pub fn add(&mut self, label: impl Into<String>, client: LimelightClient) {
+        let label = label.into();
+        tracing::debug!("Adding camera '{}' to group", label);
+        self.cameras.retain(|(existing, _)| *existing != label);
+        self.cameras.push((label, client));
+    }
+
+    pub fn client(&self, label: &str) -> Option<&LimelightClient> {
+        self.cameras.iter().find(|(existing, _)| existing == label).map(|(_, client)| client)
+    }
+
+    pub fn cameras(&self) -> &[(String, LimelightClient)] {
+        &self.cameras
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.cameras.iter().map(|(label, _)| label.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.cameras.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cameras.is_empty()
+    }
+
+    pub async fn start_all(&self) -> Result<(), LimelightError> {
+        for (_, client) in &self.cameras {
+            client.start().await?;
+        }
+        Ok(())
+    }
+
+    pub async fn stop_all(&self) {
+        for (_, client) in &self.cameras {
+            client.stop().await;
+        }
+    }
+
+    // Every camera's results on one channel, tagged with the camera's label. Cameras added
+    // after this call are not included.
+    pub fn subscribe(&self) -> broadcast::Receiver<(String, LimelightResult)> {
+        merge_subscriptions(self.cameras.iter().map(|(label, client)| (label.as_str(), client)))
+    }
+
+    // The most recent result polled by that camera, None for an unknown label or before the first poll
+    pub fn get_latest(&self, label: &str) -> Option<LimelightResult> {
+        self.client(label)?.subscribe_watch().borrow().clone()
+    }
+}
@@ -1,7 +1,32 @@
+// LimelightError carries tungstenite's large error type by value
+#![allow(clippy::result_large_err)]
+
+mod archive;
+#[cfg(feature = "blocking")]
+mod blocking;
 mod client;
+mod clock;
 mod error;
+mod group;
+#[cfg(feature = "mdns")]
+mod mdns;
+mod metrics;
 mod models;
+mod pool;
+mod pose;
+mod replay;
+mod sink;
 
-pub use client::{LimelightClient, LimelightConfig};
+pub use archive::SnapshotArchiveHandle;
+#[cfg(feature = "blocking")]
+pub use blocking::BlockingLimelightClient;
+pub use client::{ChannelMode, LimelightClient, LimelightConfig, LimelightConfigBuilder, SlowSubscriberPolicy};
+pub use clock::{Clock, IntervalClock, ManualClock, SleepClock, TickFuture, Ticker};
 pub use error::LimelightError;
-pub use models::*;
\ No newline at end of file
+pub use group::LimelightGroup;
+pub use metrics::{PollErrorKind, PollMetrics, SlowdownReason};
+pub use models::*;
+pub use pool::{LimelightPool, MountOffset};
+pub use pose::*;
+pub use replay::{RecordedFrame, SessionRecorder, SessionReplayer};
+pub use sink::{CsvColumn, CsvSink, CsvSinkHandle};
\ No newline at end of file
@@ -0,0 +1,48 @@
+use crate::LimelightError;
+use mdns_sd::{HostnameResolutionEvent, ServiceDaemon};
+use std::net::IpAddr;
+use tokio::time::Duration;
+
+pub(crate) fn is_mdns_host(host: &str) -> bool {
+    host.trim_end_matches('.').ends_with(".local")
+}
+
+// Prefers an IPv4 address when the host answers with several
+pub(crate) async fn resolve(host: &str, timeout: Duration) -> Result<IpAddr, LimelightError> {
+    let failed = |reason: String| {
+        LimelightError::ConfigError(format!("Failed to resolve mDNS hostname '{}': {}", host, reason))
+    };
+    let fqdn = format!("{}.", host.trim_end_matches('.'));
+    tracing::debug!("Resolving mDNS hostname {}", fqdn);
+
+    let daemon = ServiceDaemon::new().map_err(|e| failed(e.to_string()))?;
+    let events = daemon
+        .resolve_hostname(&fqdn, Some(timeout.as_millis() as u64))
+        .map_err(|e| failed(e.to_string()))?;
+
+    let mut resolved = None;
+    while let Ok(event) = events.recv_async().await {
+        match event {
+            HostnameResolutionEvent::AddressesFound(_, addrs) => {
+                let addrs: Vec<IpAddr> = addrs.iter().map(|a| a.to_ip_addr()).collect();
+                resolved = addrs.iter().find(|a| a.is_ipv4()).or(addrs.first()).copied();
+                if resolved.is_some() {
+                    break;
+                }
+            }
+            HostnameResolutionEvent::SearchTimeout(_) | HostnameResolutionEvent::SearchStopped(_) => break,
+            _ => {}
+        }
+    }
+    if let Err(e) = daemon.shutdown() {
+        tracing::debug!("mDNS daemon shutdown failed: {:?}", e);
+    }
+
+    match resolved {
+        Some(addr) => {
+            tracing::debug!("Resolved {} to {}", host, addr);
+            Ok(addr)
+        }
+        None => Err(failed("no response before timeout".into())),
+    }
+}
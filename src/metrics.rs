@@ -0,0 +1,112 @@
+use crate::LimelightError;
+use tokio::time::{Duration, Instant};
+
+// Weight of the newest sample in the moving averages
+const SMOOTHING: f64 = 0.2;
+// Measured interval may exceed the requested one by this fraction before we call it slow
+const KEEP_UP_TOLERANCE: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PollErrorKind {
+    Timeout,
+    Parse,
+    Network,
+    RateLimited,
+    Other,
+}
+
+impl PollErrorKind {
+    pub(crate) fn classify(error: &LimelightError) -> Self {
+        match error {
+            LimelightError::TimeoutError => PollErrorKind::Timeout,
+            LimelightError::HttpError(e) if e.is_decode() => PollErrorKind::Parse,
+            LimelightError::HttpError(_) => PollErrorKind::Network,
+            LimelightError::JsonError(_) => PollErrorKind::Parse,
+            LimelightError::RateLimited(_) => PollErrorKind::RateLimited,
+            _ => PollErrorKind::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlowdownReason {
+    SlowRequests,
+    Timeouts,
+    ParseErrors,
+    NetworkErrors,
+    RateLimited,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PollMetrics {
+    pub successes: u64,
+    pub timeouts: u64,
+    pub parse_errors: u64,
+    pub network_errors: u64,
+    pub rate_limited: u64,
+    pub other_errors: u64,
+    pub measured_interval_ms: Option<f64>,
+    pub mean_fetch_ms: Option<f64>,
+    pub last_error: Option<PollErrorKind>,
+    pub last_success_at: Option<Instant>,
+}
+
+impl PollMetrics {
+    pub(crate) fn record_success(&mut self, fetch_duration: Duration) {
+        let now = Instant::now();
+        if let Some(previous) = self.last_success_at {
+            let interval = now.duration_since(previous).as_secs_f64() * 1000.0;
+            self.measured_interval_ms = Some(smooth(self.measured_interval_ms, interval));
+        }
+        self.mean_fetch_ms = Some(smooth(self.mean_fetch_ms, fetch_duration.as_secs_f64() * 1000.0));
+        self.last_success_at = Some(now);
+        self.last_error = None;
+        self.successes += 1;
+    }
+
+    pub(crate) fn record_error(&mut self, kind: PollErrorKind) {
+        match kind {
+            PollErrorKind::Timeout => self.timeouts += 1,
+            PollErrorKind::Parse => self.parse_errors += 1,
+            PollErrorKind::Network => self.network_errors += 1,
+            PollErrorKind::RateLimited => self.rate_limited += 1,
+            PollErrorKind::Other => self.other_errors += 1,
+        }
+        self.last_error = Some(kind);
+    }
+
+    // The effective interval also counts the time since the last success, so a loop
+    // that has stopped producing results doesn't look healthy from a stale average
+    pub fn effective_interval_ms(&self) -> Option<f64> {
+        let since_last = self.last_success_at?.elapsed().as_secs_f64() * 1000.0;
+        Some(self.measured_interval_ms.unwrap_or(since_last).max(since_last))
+    }
+
+    pub fn is_keeping_up(&self, requested_interval_ms: u64) -> bool {
+        let limit = requested_interval_ms as f64 * (1.0 + KEEP_UP_TOLERANCE) + 1.0;
+        self.effective_interval_ms().is_some_and(|interval| interval <= limit)
+    }
+
+    pub fn slowdown_reason(&self, requested_interval_ms: u64) -> Option<SlowdownReason> {
+        if self.is_keeping_up(requested_interval_ms) {
+            return None;
+        }
+        Some(match self.last_error {
+            Some(PollErrorKind::Timeout) => SlowdownReason::Timeouts,
+            Some(PollErrorKind::Parse) => SlowdownReason::ParseErrors,
+            Some(PollErrorKind::Network) => SlowdownReason::NetworkErrors,
+            Some(PollErrorKind::RateLimited) => SlowdownReason::RateLimited,
+            Some(PollErrorKind::Other) => SlowdownReason::Unknown,
+            None if self.mean_fetch_ms.is_some_and(|ms| ms > requested_interval_ms as f64) => SlowdownReason::SlowRequests,
+            None => SlowdownReason::Unknown,
+        })
+    }
+}
+
+fn smooth(average: Option<f64>, sample: f64) -> f64 {
+    match average {
+        Some(average) => average + SMOOTHING * (sample - average),
+        None => sample,
+    }
+}
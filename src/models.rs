@@ -1,22 +1,31 @@
-use serde::Deserialize;
+use crate::{scaled_std_devs, MegaTagVersion, Pose2d, Pose3d, PoseOrigin, PoseStdDevs};
+use serde::de::{self, DeserializeOwned};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 #[serde(default)]
+#[allow(non_snake_case)]
 pub struct LimelightResult {
     #[serde(default)]
-    #[serde(rename = "Barcode")]
+    #[serde(rename = "Barcode", deserialize_with = "lenient_vec")]
     pub barcode: Vec<BarcodeResult>,
     #[serde(default)]
-    #[serde(rename = "Classifier")]
+    #[serde(rename = "Classifier", deserialize_with = "lenient_vec")]
     pub classifier: Vec<ClassifierResult>,
     #[serde(default)]
-    #[serde(rename = "Detector")]
+    #[serde(rename = "Detector", deserialize_with = "lenient_vec")]
     pub detector: Vec<DetectorResult>,
     #[serde(default)]
-    #[serde(rename = "Fiducial")]
+    #[serde(rename = "Fiducial", deserialize_with = "lenient_vec")]
     pub fiducial: Vec<FiducialResult>,
     #[serde(default)]
-    #[serde(rename = "Retro")]
+    #[serde(rename = "Retro", deserialize_with = "lenient_vec")]
     pub retro: Vec<ColorResult>,
     pub pipeline_type: Option<String>,
     pub tx: Option<f64>,
@@ -27,14 +36,17 @@ pub struct LimelightResult {
     pub ts: Option<f64>,
     pub v: Option<f64>,
     pub focus_metric: Option<f64>,
+    #[serde(deserialize_with = "pose_array")]
     pub botpose: Option<Vec<f64>>,
+    #[serde(deserialize_with = "pose_array")]
     pub botpose_wpiblue: Option<Vec<f64>>,
+    #[serde(deserialize_with = "pose_array")]
     pub botpose_wpired: Option<Vec<f64>>,
-    #[serde(rename = "botpose_orb")]
+    #[serde(rename = "botpose_orb", deserialize_with = "pose_array")]
     pub botposeMT2: Option<Vec<f64>>,
-    #[serde(rename = "botpose_orb_wpiblue")]
+    #[serde(rename = "botpose_orb_wpiblue", deserialize_with = "pose_array")]
     pub botposeMT2_wpiblue: Option<Vec<f64>>,
-    #[serde(rename = "botpose_orb_wpired")]
+    #[serde(rename = "botpose_orb_wpired", deserialize_with = "pose_array")]
     pub botposeMT2_wpired: Option<Vec<f64>>,
     pub stdev_mt1: Option<Vec<f64>>,
     pub stdev_mt2: Option<Vec<f64>>,
@@ -49,45 +61,460 @@ pub struct LimelightResult {
     pub t6c_rs: Option<Vec<f64>>,
 }
 
-impl Default for LimelightResult {
-    fn default() -> Self {
-        Self {
-            barcode: Vec::new(),
-            classifier: Vec::new(),
-            detector: Vec::new(),
-            fiducial: Vec::new(),
-            retro: Vec::new(),
-            pipeline_type: None,
-            tx: None,
-            ty: None,
-            ta: None,
-            cl: None,
-            tl: None,
-            ts: None,
-            v: None,
-            focus_metric: None,
-            botpose: None,
-            botpose_wpiblue: None,
-            botpose_wpired: None,
-            botposeMT2: None,
-            botposeMT2_wpiblue: None,
-            botposeMT2_wpired: None,
-            stdev_mt1: None,
-            stdev_mt2: None,
-            botpose_tagcount: None,
-            botpose_span: None,
-            botpose_avgdist: None,
-            botpose_avgarea: None,
-            python_out: None,
-            txnc: None,
-            tync: None,
-            pipeline_id: None,
-            t6c_rs: None,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Deserialize)]
+impl LimelightResult {
+    // `v` is reported as 0.0/1.0; a missing flag counts as no target
+    pub fn valid(&self) -> bool {
+        self.v.is_some_and(|v| v > 0.0)
+    }
+
+    // Same as valid()
+    pub fn has_valid_target(&self) -> bool {
+        self.valid()
+    }
+
+    // Fiducial, detector, classifier and color targets; barcodes are not counted
+    pub fn valid_target_count(&self) -> usize {
+        self.fiducial.len() + self.detector.len() + self.classifier.len() + self.retro.len()
+    }
+
+    pub fn pose(&self, origin: PoseOrigin, version: MegaTagVersion) -> Option<Pose3d> {
+        let values = match (origin, version) {
+            (PoseOrigin::Field, MegaTagVersion::MegaTag1) => &self.botpose,
+            (PoseOrigin::WpiBlue, MegaTagVersion::MegaTag1) => &self.botpose_wpiblue,
+            (PoseOrigin::WpiRed, MegaTagVersion::MegaTag1) => &self.botpose_wpired,
+            (PoseOrigin::Field, MegaTagVersion::MegaTag2) => &self.botposeMT2,
+            (PoseOrigin::WpiBlue, MegaTagVersion::MegaTag2) => &self.botposeMT2_wpiblue,
+            (PoseOrigin::WpiRed, MegaTagVersion::MegaTag2) => &self.botposeMT2_wpired,
+        };
+        values.as_deref().and_then(Pose3d::from_array)
+    }
+
+    // Like pose(), reduced to the plane
+    pub fn pose2d(&self, origin: PoseOrigin, version: MegaTagVersion) -> Option<Pose2d> {
+        self.pose(origin, version).map(|pose| pose.to_pose2d())
+    }
+
+    // Named accessors for each botpose field; None when the array is missing or too short
+    pub fn botpose_as_pose3d(&self) -> Option<Pose3d> {
+        self.pose(PoseOrigin::Field, MegaTagVersion::MegaTag1)
+    }
+
+    pub fn botpose_as_pose2d(&self) -> Option<Pose2d> {
+        self.pose2d(PoseOrigin::Field, MegaTagVersion::MegaTag1)
+    }
+
+    pub fn botpose_wpiblue_as_pose3d(&self) -> Option<Pose3d> {
+        self.pose(PoseOrigin::WpiBlue, MegaTagVersion::MegaTag1)
+    }
+
+    pub fn botpose_wpiblue_as_pose2d(&self) -> Option<Pose2d> {
+        self.pose2d(PoseOrigin::WpiBlue, MegaTagVersion::MegaTag1)
+    }
+
+    pub fn botpose_wpired_as_pose3d(&self) -> Option<Pose3d> {
+        self.pose(PoseOrigin::WpiRed, MegaTagVersion::MegaTag1)
+    }
+
+    pub fn botpose_wpired_as_pose2d(&self) -> Option<Pose2d> {
+        self.pose2d(PoseOrigin::WpiRed, MegaTagVersion::MegaTag1)
+    }
+
+    pub fn botpose_mt2_as_pose3d(&self) -> Option<Pose3d> {
+        self.pose(PoseOrigin::Field, MegaTagVersion::MegaTag2)
+    }
+
+    pub fn botpose_mt2_as_pose2d(&self) -> Option<Pose2d> {
+        self.pose2d(PoseOrigin::Field, MegaTagVersion::MegaTag2)
+    }
+
+    pub fn botpose_mt2_wpiblue_as_pose3d(&self) -> Option<Pose3d> {
+        self.pose(PoseOrigin::WpiBlue, MegaTagVersion::MegaTag2)
+    }
+
+    pub fn botpose_mt2_wpiblue_as_pose2d(&self) -> Option<Pose2d> {
+        self.pose2d(PoseOrigin::WpiBlue, MegaTagVersion::MegaTag2)
+    }
+
+    pub fn botpose_mt2_wpired_as_pose3d(&self) -> Option<Pose3d> {
+        self.pose(PoseOrigin::WpiRed, MegaTagVersion::MegaTag2)
+    }
+
+    pub fn botpose_mt2_wpired_as_pose2d(&self) -> Option<Pose2d> {
+        self.pose2d(PoseOrigin::WpiRed, MegaTagVersion::MegaTag2)
+    }
+
+    // scaled_std_devs using this result's botpose_avgdist and botpose_tagcount
+    pub fn scaled_std_devs(&self, base: &PoseStdDevs) -> Option<PoseStdDevs> {
+        Some(scaled_std_devs(base, self.botpose_avgdist?, self.botpose_tagcount?))
+    }
+
+    // True if any of the six botpose fields carries data; detector and classifier pipelines never do
+    pub fn has_any_pose(&self) -> bool {
+        [
+            &self.botpose,
+            &self.botpose_wpiblue,
+            &self.botpose_wpired,
+            &self.botposeMT2,
+            &self.botposeMT2_wpiblue,
+            &self.botposeMT2_wpired,
+        ]
+        .iter()
+        .any(|values| values.as_ref().is_some_and(|v| !v.is_empty()))
+    }
+
+    // Capture latency plus pipeline latency, in milliseconds
+    pub fn total_latency_ms(&self) -> Option<f64> {
+        Some(self.cl? + self.tl?)
+    }
+
+    // When the frame was captured, given when this result arrived; None without both
+    // latencies or if they are negative
+    pub fn capture_timestamp(&self, received_at: Instant) -> Option<Instant> {
+        let latency = Duration::try_from_secs_f64(self.total_latency_ms()? / 1000.0).ok()?;
+        received_at.checked_sub(latency)
+    }
+
+    // Largest first; fiducials without an area are left out
+    pub fn fiducials_by_area(&self) -> Vec<&FiducialResult> {
+        let mut fiducials: Vec<&FiducialResult> = self.fiducial.iter().filter(|f| f.ta.is_some()).collect();
+        fiducials.sort_by(|a, b| b.ta.unwrap_or(0.0).total_cmp(&a.ta.unwrap_or(0.0)));
+        fiducials
+    }
+
+    // (fID, tag pose in camera space) for every fiducial carrying a full t6t_cs transform
+    pub fn fiducial_camera_poses(&self) -> Vec<(i32, Pose3d)> {
+        self.fiducial
+            .iter()
+            .filter_map(|f| Some((f.f_id?, f.t6t_cs.as_deref().and_then(Pose3d::from_array)?)))
+            .collect()
+    }
+
+    // Tag B's pose in tag A's frame, from their camera-space poses
+    pub fn relative_tag_pose(&self, id_a: i32, id_b: i32) -> Option<Pose3d> {
+        let poses = self.fiducial_camera_poses();
+        let find = |id: i32| poses.iter().find(|(f_id, _)| *f_id == id).map(|(_, pose)| *pose);
+        Some(find(id_a)?.relative_to(&find(id_b)?))
+    }
+
+    pub fn fiducials_of_family(&self, family: &TagFamily) -> Vec<&FiducialResult> {
+        self.fiducial.iter().filter(|f| f.is_family(family)).collect()
+    }
+
+    // Entries without both tx and ty are skipped; classifier results carry no position
+    pub fn all_targets(&self) -> Vec<Target> {
+        let fiducials = self.fiducial.iter().map(|f| (TargetKind::Fiducial, f.tx, f.ty, f.ta));
+        let detections = self.detector.iter().map(|d| (TargetKind::Detector, d.tx, d.ty, d.ta));
+        let retro = self.retro.iter().map(|r| (TargetKind::Retro, r.tx, r.ty, r.ta));
+        let barcodes = self.barcode.iter().map(|b| (TargetKind::Barcode, b.tx, b.ty, b.ta));
+
+        fiducials
+            .chain(detections)
+            .chain(retro)
+            .chain(barcodes)
+            .filter_map(|(kind, tx, ty, ta)| Some(Target { kind, tx: tx?, ty: ty?, ta }))
+            .collect()
+    }
+
+    // Mean (tx, ty) over all_targets(); None when nothing is detected
+    pub fn target_centroid(&self) -> Option<(f64, f64)> {
+        centroid(self.all_targets().iter().map(|t| (t.tx, t.ty, 1.0)))
+    }
+
+    // Like target_centroid, weighted by area; targets without a positive `ta` are ignored
+    pub fn weighted_target_centroid(&self) -> Option<(f64, f64)> {
+        centroid(self.all_targets().iter().filter_map(|t| Some((t.tx, t.ty, t.ta.filter(|ta| *ta > 0.0)?))))
+    }
+
+    // Caps every per-type target array at `max` entries, returning how many were dropped
+    pub fn truncate_targets(&mut self, max: usize) -> usize {
+        fn cap<T>(targets: &mut Vec<T>, max: usize) -> usize {
+            let dropped = targets.len().saturating_sub(max);
+            targets.truncate(max);
+            dropped
+        }
+        cap(&mut self.barcode, max)
+            + cap(&mut self.classifier, max)
+            + cap(&mut self.detector, max)
+            + cap(&mut self.fiducial, max)
+            + cap(&mut self.retro, max)
+    }
+
+    // Hash of what the pipeline saw: pipeline, targets and MegaTag1 pose. Timestamps and
+    // latencies are left out so an unchanged scene hashes the same frame to frame.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.pipeline_id.hash(&mut hasher);
+        self.pipeline_type.hash(&mut hasher);
+        hash_floats(&mut hasher, [self.v, self.tx, self.ty, self.ta]);
+        hash_floats(&mut hasher, self.botpose.iter().flatten().take(6).map(|v| Some(*v)));
+        self.botpose_tagcount.hash(&mut hasher);
+        for target in self.all_targets() {
+            (target.kind as u8).hash(&mut hasher);
+            hash_floats(&mut hasher, [Some(target.tx), Some(target.ty), target.ta]);
+        }
+        for f in &self.fiducial {
+            f.f_id.hash(&mut hasher);
+        }
+        for d in &self.detector {
+            d.class_id.hash(&mut hasher);
+        }
+        for c in &self.classifier {
+            c.class_id.hash(&mut hasher);
+        }
+        for b in &self.barcode {
+            b.data.hash(&mut hasher);
+        }
+        hash_floats(&mut hasher, self.python_out.iter().flatten().map(|v| Some(*v)));
+        hasher.finish()
+    }
+
+    // Only the data the reported pipeline_type actually populates
+    pub fn as_view(&self) -> ResultView<'_> {
+        match self.pipeline_type.as_deref().map(PipelineType::from) {
+            Some(PipelineType::Fiducial) => ResultView::Fiducial {
+                botpose: self.pose(PoseOrigin::Field, MegaTagVersion::MegaTag1),
+                tags: &self.fiducial,
+            },
+            Some(PipelineType::NeuralDetector) => ResultView::Detector { detections: &self.detector },
+            Some(PipelineType::NeuralClassifier) => ResultView::Classifier { classifications: &self.classifier },
+            Some(PipelineType::Barcode) => ResultView::Barcode { barcodes: &self.barcode },
+            Some(PipelineType::Color) => ResultView::Color { targets: &self.retro },
+            Some(PipelineType::Python) => ResultView::Python { outputs: self.python_out.as_deref().unwrap_or_default() },
+            Some(PipelineType::Other(_)) | None => ResultView::Unknown { pipeline_type: self.pipeline_type.as_deref() },
+        }
+    }
+}
+
+// Pipeline kinds as the firmware names them ("pipe_fiducial", ...)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PipelineType {
+    Fiducial,
+    NeuralDetector,
+    NeuralClassifier,
+    Barcode,
+    Color,
+    Python,
+    Other(String),
+}
+
+impl PipelineType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            PipelineType::Fiducial => "pipe_fiducial",
+            PipelineType::NeuralDetector => "pipe_neuraldetector",
+            PipelineType::NeuralClassifier => "pipe_neuralclassifier",
+            PipelineType::Barcode => "pipe_barcode",
+            PipelineType::Color => "pipe_color",
+            PipelineType::Python => "pipe_python",
+            PipelineType::Other(name) => name,
+        }
+    }
+}
+
+impl From<&str> for PipelineType {
+    fn from(name: &str) -> Self {
+        match name {
+            "pipe_fiducial" => PipelineType::Fiducial,
+            "pipe_neuraldetector" => PipelineType::NeuralDetector,
+            "pipe_neuralclassifier" => PipelineType::NeuralClassifier,
+            "pipe_barcode" => PipelineType::Barcode,
+            "pipe_color" => PipelineType::Color,
+            "pipe_python" => PipelineType::Python,
+            other => PipelineType::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for PipelineType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ResultView<'a> {
+    Fiducial { botpose: Option<Pose3d>, tags: &'a [FiducialResult] },
+    Detector { detections: &'a [DetectorResult] },
+    Classifier { classifications: &'a [ClassifierResult] },
+    Barcode { barcodes: &'a [BarcodeResult] },
+    Color { targets: &'a [ColorResult] },
+    Python { outputs: &'a [f64] },
+    Unknown { pipeline_type: Option<&'a str> },
+}
+
+fn centroid(points: impl Iterator<Item = (f64, f64, f64)>) -> Option<(f64, f64)> {
+    let (tx, ty, weight) = points.fold((0.0, 0.0, 0.0), |(sx, sy, sw), (x, y, w)| (sx + x * w, sy + y * w, sw + w));
+    (weight > 0.0).then(|| (tx / weight, ty / weight))
+}
+
+// Accepts the array form [x, y, z, roll, pitch, yaw, latency, tagcount, span, avgdist, avgarea]
+// or an object with those names, normalizing the object into the array layout. Object keys
+// past yaw are optional; missing ones end the array there. An object without all six pose
+// keys is dropped rather than failing the whole result.
+fn pose_array<'de, D>(deserializer: D) -> Result<Option<Vec<f64>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    const KEYS: [&str; 11] = ["x", "y", "z", "roll", "pitch", "yaw", "latency", "tagcount", "span", "avgdist", "avgarea"];
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Object(fields)) => {
+            let values: Vec<f64> = KEYS.iter().map_while(|key| fields.get(*key).and_then(Value::as_f64)).collect();
+            if values.len() < 6 {
+                tracing::debug!("Ignoring pose object without x, y, z, roll, pitch and yaw: {:?}", fields);
+                return Ok(None);
+            }
+            Ok(Some(values))
+        }
+        Some(other) => Vec::<f64>::deserialize(other).map(Some).map_err(de::Error::custom),
+    }
+}
+
+fn lenient_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(s)) => Some(s),
+        Some(Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+// Whole numbers only, including integral floats like 1280.0; anything else becomes None
+fn lenient_u32<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<Value>::deserialize(deserializer)?
+        .as_ref()
+        .and_then(Value::as_f64)
+        .filter(|v| v.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(v))
+        .map(|v| v as u32))
+}
+
+// Deserializes each array entry on its own so one malformed sub-object is logged and
+// skipped rather than failing the whole result. A non-array value yields an empty Vec.
+fn lenient_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let entries = match Value::deserialize(deserializer)? {
+        Value::Array(entries) => entries,
+        Value::Null => return Ok(Vec::new()),
+        other => {
+            tracing::warn!("Expected an array of results, got {}", other);
+            return Ok(Vec::new());
+        }
+    };
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| match T::deserialize(&entry) {
+            Ok(parsed) => Some(parsed),
+            Err(e) => {
+                tracing::warn!("Skipping malformed result entry {}: {}", entry, e);
+                None
+            }
+        })
+        .collect())
+}
+
+// -0.0 and 0.0 hash alike; NaN hashes by its bit pattern
+fn hash_floats(hasher: &mut DefaultHasher, values: impl IntoIterator<Item = Option<f64>>) {
+    for value in values {
+        value.map(|v| if v == 0.0 { 0 } else { v.to_bits() }).hash(hasher);
+    }
+}
+
+// Fiducial IDs visible in both frames
+pub fn shared_tag_count(prev: &LimelightResult, cur: &LimelightResult) -> usize {
+    let prev_ids = tag_ids(prev);
+    tag_ids(cur).intersection(&prev_ids).count()
+}
+
+// True when fewer than half of the larger frame's tags carried over, a hint that a
+// sudden pose jump between the two frames deserves suspicion
+pub fn tag_set_changed(prev: &LimelightResult, cur: &LimelightResult) -> bool {
+    let largest = tag_ids(prev).len().max(tag_ids(cur).len());
+    shared_tag_count(prev, cur) * 2 < largest
+}
+
+fn tag_ids(result: &LimelightResult) -> BTreeSet<i32> {
+    result.fiducial.iter().filter_map(|f| f.f_id).collect()
+}
+
+// LED control; Pipeline defers to the active pipeline's own LED setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LedMode {
+    Pipeline,
+    ForceOff,
+    ForceBlink,
+    ForceOn,
+}
+
+impl LedMode {
+    pub fn value(&self) -> u8 {
+        match self {
+            LedMode::Pipeline => 0,
+            LedMode::ForceOff => 1,
+            LedMode::ForceBlink => 2,
+            LedMode::ForceOn => 3,
+        }
+    }
+}
+
+// Layout of the video stream when a secondary camera is attached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamMode {
+    Standard,
+    PipMain,
+    PipSecondary,
+}
+
+impl StreamMode {
+    pub fn value(&self) -> u8 {
+        match self {
+            StreamMode::Standard => 0,
+            StreamMode::PipMain => 1,
+            StreamMode::PipSecondary => 2,
+        }
+    }
+}
+
+// Edges of the `v` flag between consecutive results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TargetEvent {
+    Acquired,
+    Lost,
+}
+
+// Transitions of the poll loop's link to the camera. Connected is the first success ever,
+// Reconnected the first success after a Disconnected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionState {
+    Connected,
+    Disconnected,
+    Reconnected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TargetKind {
+    Fiducial,
+    Detector,
+    Retro,
+    Barcode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Target {
+    pub kind: TargetKind,
+    pub tx: f64,
+    pub ty: f64,
+    pub ta: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct BarcodeResult {
     pub fam: Option<String>,
     pub data: Option<String>,
@@ -101,24 +528,7 @@ pub struct BarcodeResult {
     pub pts: Option<Vec<Vec<f64>>>,
 }
 
-impl Default for BarcodeResult {
-    fn default() -> Self {
-        Self {
-            fam: None,
-            data: None,
-            txp: None,
-            typ: None,
-            tx: None,
-            ty: None,
-            tx_nocross: None,
-            ty_nocross: None,
-            ta: None,
-            pts: None,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct ClassifierResult {
     pub class: Option<String>,
     #[serde(rename = "classID")]
@@ -126,17 +536,7 @@ pub struct ClassifierResult {
     pub conf: Option<f64>,
 }
 
-impl Default for ClassifierResult {
-    fn default() -> Self {
-        Self {
-            class: None,
-            class_id: None,
-            conf: None,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct DetectorResult {
     pub class: Option<String>,
     #[serde(rename = "classID")]
@@ -152,29 +552,34 @@ pub struct DetectorResult {
     pub pts: Option<Vec<Vec<f64>>>,
 }
 
-impl Default for DetectorResult {
-    fn default() -> Self {
-        Self {
-            class: None,
-            class_id: None,
-            conf: None,
-            ta: None,
-            txp: None,
-            typ: None,
-            tx: None,
-            ty: None,
-            tx_nocross: None,
-            ty_nocross: None,
-            pts: None,
+impl DetectorResult {
+    // Width over height of the axis-aligned box around `pts`; None without at least two
+    // well-formed [x, y] points or with a zero-height box
+    pub fn aspect_ratio(&self) -> Option<f64> {
+        let pts = self.pts.as_ref()?;
+        if pts.len() < 2 || pts.iter().any(|p| p.len() < 2 || !p[0].is_finite() || !p[1].is_finite()) {
+            return None;
         }
+        let (min_x, max_x, min_y, max_y) = pts.iter().fold(
+            (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY),
+            |(min_x, max_x, min_y, max_y), p| (min_x.min(p[0]), max_x.max(p[0]), min_y.min(p[1]), max_y.max(p[1])),
+        );
+        let height = max_y - min_y;
+        (height > 0.0).then(|| (max_x - min_x) / height)
+    }
+
+    // Pixels from the image center to (txp, typ), positive right and down
+    pub fn pixel_offset_from_center(&self, width: u32, height: u32) -> Option<(f64, f64)> {
+        Some((self.txp? - width as f64 / 2.0, self.typ? - height as f64 / 2.0))
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct FiducialResult {
     #[serde(rename = "fID")]
     pub f_id: Option<i32>,
     pub fam: Option<String>,
+    pub ambiguity: Option<f64>,
     pub skew: Option<Vec<f64>>,
     pub t6c_ts: Option<Vec<f64>>,
     pub t6r_fs: Option<Vec<f64>>,
@@ -192,31 +597,80 @@ pub struct FiducialResult {
     pub pts: Option<Vec<Vec<f64>>>,
 }
 
-impl Default for FiducialResult {
-    fn default() -> Self {
-        Self {
-            f_id: None,
-            fam: None,
-            skew: None,
-            t6c_ts: None,
-            t6r_fs: None,
-            t6r_fs_orb: None,
-            t6r_ts: None,
-            t6t_cs: None,
-            t6t_rs: None,
-            ta: None,
-            txp: None,
-            typ: None,
-            tx: None,
-            ty: None,
-            tx_nocross: None,
-            ty_nocross: None,
-            pts: None,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Deserialize)]
+impl FiducialResult {
+    // Ratio of best to alternate solvePnP reprojection error, 0..=1; higher means the
+    // single-tag pose is more likely flipped. Values outside that range read as None.
+    pub fn pose_ambiguity(&self) -> Option<f64> {
+        self.ambiguity.filter(|a| (0.0..=1.0).contains(a))
+    }
+
+    pub fn family(&self) -> Option<TagFamily> {
+        self.fam.as_deref().map(TagFamily::from)
+    }
+
+    pub fn is_family(&self, family: &TagFamily) -> bool {
+        self.family().as_ref() == Some(family)
+    }
+
+    pub fn skew_components(&self) -> Option<Skew> {
+        match self.skew.as_deref()? {
+            [horizontal, vertical] => Some(Skew { horizontal: *horizontal, vertical: *vertical }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TagFamily {
+    Tag16h5,
+    Tag25h9,
+    Tag36h10,
+    Tag36h11,
+    TagCircle21h7,
+    TagStandard41h12,
+    Other(String),
+}
+
+// Firmware reports families like "36H11C"; case, a "tag" prefix and the trailing "C" are ignored
+impl From<&str> for TagFamily {
+    fn from(fam: &str) -> Self {
+        let normalized = fam.trim().to_ascii_lowercase();
+        let normalized = normalized.strip_prefix("tag").unwrap_or(&normalized);
+        let normalized = normalized.strip_suffix('c').unwrap_or(normalized);
+        match normalized {
+            "16h5" => TagFamily::Tag16h5,
+            "25h9" => TagFamily::Tag25h9,
+            "36h10" => TagFamily::Tag36h10,
+            "36h11" => TagFamily::Tag36h11,
+            "circle21h7" => TagFamily::TagCircle21h7,
+            "standard41h12" => TagFamily::TagStandard41h12,
+            _ => TagFamily::Other(fam.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for TagFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagFamily::Tag16h5 => write!(f, "16h5"),
+            TagFamily::Tag25h9 => write!(f, "25h9"),
+            TagFamily::Tag36h10 => write!(f, "36h10"),
+            TagFamily::Tag36h11 => write!(f, "36h11"),
+            TagFamily::TagCircle21h7 => write!(f, "circle21h7"),
+            TagFamily::TagStandard41h12 => write!(f, "standard41h12"),
+            TagFamily::Other(fam) => write!(f, "{}", fam),
+        }
+    }
+}
+
+// Perspective skew of the tag outline as [horizontal, vertical], in degrees
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Skew {
+    pub horizontal: f64,
+    pub vertical: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct ColorResult {
     pub t6c_ts: Option<Vec<f64>>,
     pub t6r_fs: Option<Vec<f64>>,
@@ -233,22 +687,276 @@ pub struct ColorResult {
     pub pts: Option<Vec<Vec<f64>>>,
 }
 
-impl Default for ColorResult {
-    fn default() -> Self {
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NeuralNetworkInfo {
+    pub name: Option<String>,
+    pub size: Option<u64>,
+}
+
+// Which endpoints answered a probe with 2xx, keyed by endpoint path
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointSupport {
+    pub endpoints: BTreeMap<String, bool>,
+}
+
+impl EndpointSupport {
+    // False for endpoints that weren't probed
+    pub fn is_supported(&self, endpoint: &str) -> bool {
+        self.endpoints.get(endpoint).copied().unwrap_or(false)
+    }
+
+    pub fn supported(&self) -> impl Iterator<Item = &str> {
+        self.endpoints.iter().filter(|(_, ok)| **ok).map(|(name, _)| name.as_str())
+    }
+
+    pub fn unsupported(&self) -> impl Iterator<Item = &str> {
+        self.endpoints.iter().filter(|(_, ok)| !**ok).map(|(name, _)| name.as_str())
+    }
+}
+
+// Parsed /status. Field names vary between firmware releases and some send several variants
+// at once, so the first key present wins; anything else the device reports is ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LimelightStatus {
+    pub cpu_temp: Option<f64>,
+    pub cpu_usage: Option<f64>,
+    pub ram_usage: Option<f64>,
+    pub fps: Option<f64>,
+    pub pipeline_index: Option<u32>,
+    pub pipeline_type: Option<String>,
+    pub firmware_version: Option<String>,
+    // Seconds since boot
+    pub uptime: Option<f64>,
+    pub name: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for LimelightStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let status = Value::deserialize(deserializer)?;
+        let number = |keys: &[&str]| keys.iter().find_map(|key| status.get(*key)?.as_f64());
+        Ok(Self {
+            cpu_temp: number(&["cpu_temp", "temp"]),
+            cpu_usage: number(&["cpu_usage", "cpu"]),
+            ram_usage: number(&["ram_usage", "ram"]),
+            fps: number(&["fps", "finalFps"]),
+            // Integral floats such as 0.0 are accepted, as some firmware sends the index that way
+            pipeline_index: number(&["pipeline_index", "pipelineIndex"])
+                .filter(|index| index.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(index))
+                .map(|index| index as u32),
+            pipeline_type: first_string(&status, &["pipeline_type", "pipelineType"]),
+            firmware_version: first_string(&status, &["firmware_version", "version", "swversion"]),
+            uptime: number(&["uptime"]),
+            name: first_string(&status, &["name"]),
+        })
+    }
+}
+
+// First of `keys` holding a string or number, numbers rendered as strings
+fn first_string(report: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| match report.get(*key)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FirmwareInfo {
+    pub version: Option<String>,
+    pub build_date: Option<String>,
+    pub hardware_model: Option<String>,
+}
+
+impl FirmwareInfo {
+    // Key names differ between firmware releases, so the first one present wins
+    pub fn from_hardware_report(report: &Value) -> Self {
         Self {
-            t6c_ts: None,
-            t6r_fs: None,
-            t6r_ts: None,
-            t6t_cs: None,
-            t6t_rs: None,
-            ta: None,
-            txp: None,
-            typ: None,
-            tx: None,
-            ty: None,
-            tx_nocross: None,
-            ty_nocross: None,
-            pts: None,
+            version: first_string(report, &["version", "firmware_version", "swversion", "sw_version"]),
+            build_date: first_string(report, &["build_date", "builddate", "build"]),
+            hardware_model: first_string(report, &["hardware_model", "hwmodel", "model", "hw_type"]),
         }
     }
+
+    // Compares dotted numeric components ("2024.10.2" >= "2024.9"); false if either side doesn't parse
+    pub fn version_at_least(&self, minimum: &str) -> bool {
+        let parse = |v: &str| -> Option<Vec<u64>> {
+            v.trim_start_matches(['v', 'V']).split('.').map(|part| part.trim().parse().ok()).collect()
+        };
+        match (self.version.as_deref().and_then(parse), parse(minimum)) {
+            (Some(mut actual), Some(mut minimum)) => {
+                let len = actual.len().max(minimum.len());
+                actual.resize(len, 0);
+                minimum.resize(len, 0);
+                actual >= minimum
+            }
+            _ => false,
+        }
+    }
+}
+
+// Parsed /hwreport, accepting the same key variants as FirmwareInfo with the first one present
+// winning. Identifiers are kept as strings even when the firmware sends them as numbers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HardwareReport {
+    pub hardware_model: Option<String>,
+    pub serial_number: Option<String>,
+    pub firmware_version: Option<String>,
+    pub build_date: Option<String>,
+    pub sensor: Option<SensorInfo>,
+    pub pipeline_types: Vec<String>,
+}
+
+impl<'de> Deserialize<'de> for HardwareReport {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let report = Value::deserialize(deserializer)?;
+        let firmware = FirmwareInfo::from_hardware_report(&report);
+        // Firmware variance in these two is logged and dropped rather than failing the report
+        let sensor = match report.get("sensor") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(model)) => Some(SensorInfo { model: Some(model.clone()), ..Default::default() }),
+            Some(sensor @ Value::Object(_)) => SensorInfo::deserialize(sensor)
+                .map_err(|e| tracing::debug!("Ignoring unparseable sensor info {}: {}", sensor, e))
+                .ok(),
+            Some(other) => {
+                tracing::debug!("Ignoring sensor info that isn't an object: {}", other);
+                None
+            }
+        };
+        let pipeline_types = match report.get("pipelinetypes") {
+            Some(Value::Array(types)) => types
+                .iter()
+                .filter_map(|entry| {
+                    let name = entry.as_str().map(str::to_string);
+                    if name.is_none() {
+                        tracing::debug!("Ignoring non-string pipeline type {}", entry);
+                    }
+                    name
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Ok(Self {
+            hardware_model: firmware.hardware_model,
+            serial_number: first_string(&report, &["serial_number", "serial", "serialnumber"]),
+            firmware_version: firmware.version,
+            build_date: firmware.build_date,
+            sensor,
+            pipeline_types,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SensorInfo {
+    #[serde(alias = "name", deserialize_with = "lenient_string")]
+    pub model: Option<String>,
+    #[serde(deserialize_with = "lenient_u32")]
+    pub width: Option<u32>,
+    #[serde(deserialize_with = "lenient_u32")]
+    pub height: Option<u32>,
+}
+
+// One outbound command; `timestamp` is unix seconds at send time
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandRecord {
+    pub timestamp: f64,
+    pub method: String,
+    pub endpoint: String,
+    pub outcome: CommandOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandOutcome {
+    Status(u16),
+    Failed(String),
+}
+
+// Contents of a Limelight .fmap file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FieldMap {
+    #[serde(rename = "type")]
+    pub map_type: Option<String>,
+    pub fiducials: Vec<FieldMapFiducial>,
+}
+
+impl FieldMap {
+    pub fn fiducial(&self, id: i32) -> Option<&FieldMapFiducial> {
+        self.fiducials.iter().find(|f| f.id == id)
+    }
+}
+
+// `size` is the tag's edge length in millimeters; `transform` is its row-major 4x4 field pose
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FieldMapFiducial {
+    pub id: i32,
+    pub family: Option<String>,
+    pub size: f64,
+    pub transform: Vec<f64>,
+}
+
+impl FieldMapFiducial {
+    pub fn pose(&self) -> Option<Pose3d> {
+        Pose3d::from_transform_matrix(&self.transform)
+    }
+}
+
+// Intrinsics are the row-major 3x3 camera matrix. Quality metrics are only reported for a
+// freshly computed calibration (cal-latest); reprojection error is RMS in pixels.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CameraCalibration {
+    pub resx: Option<u32>,
+    pub resy: Option<u32>,
+    pub intrinsics: Option<Vec<f64>>,
+    pub distortion: Option<Vec<f64>>,
+    #[serde(alias = "reprojerror")]
+    pub reprojection_error: Option<f64>,
+    #[serde(alias = "imagecount")]
+    pub image_count: Option<u32>,
+}
+
+impl CameraCalibration {
+    pub fn is_valid(&self) -> bool {
+        self.intrinsics.as_ref().is_some_and(|m| m.len() == 9 && m[0] > 0.0 && m[4] > 0.0)
+    }
+
+    pub fn fx(&self) -> Option<f64> {
+        self.intrinsic(0)
+    }
+
+    pub fn fy(&self) -> Option<f64> {
+        self.intrinsic(4)
+    }
+
+    pub fn cx(&self) -> Option<f64> {
+        self.intrinsic(2)
+    }
+
+    pub fn cy(&self) -> Option<f64> {
+        self.intrinsic(5)
+    }
+
+    // Valid intrinsics and a reported reprojection error no larger than `max_reproj_error`
+    pub fn is_calibration_acceptable(&self, max_reproj_error: f64) -> bool {
+        self.is_valid() && self.reprojection_error.is_some_and(|error| error <= max_reproj_error)
+    }
+
+    fn intrinsic(&self, index: usize) -> Option<f64> {
+        self.intrinsics.as_ref().filter(|m| m.len() == 9).map(|m| m[index])
+    }
+}
+
+// Latency figures are in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+    pub stddev_ms: f64,
+    pub mean_round_trip_ms: f64,
 }
\ No newline at end of file
@@ -0,0 +1,138 @@
+use crate::{LimelightClient, LimelightError, LimelightResult};
+use tokio::sync::{broadcast, watch};
+
+// Capacity of each merged subscription returned by LimelightPool/LimelightGroup::subscribe
+const MERGED_CHANNEL_CAPACITY: usize = 100;
+
+// How a camera is rotated relative to the robot's forward axis, in degrees.
+// Added to that camera's tx/ty so every camera reports in the same frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MountOffset {
+    pub yaw_degrees: f64,
+    pub pitch_degrees: f64,
+}
+
+struct PoolMember {
+    label: String,
+    client: LimelightClient,
+    mount: MountOffset,
+    latest: watch::Receiver<Option<LimelightResult>>,
+}
+
+#[derive(Default)]
+pub struct LimelightPool {
+    members: Vec<PoolMember>,
+}
+
+impl LimelightPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Replaces any camera already registered under the same label
+    pub fn add(&mut self, label: impl Into<String>, client: LimelightClient, mount: MountOffset) {
+        let label = label.into();
+        tracing::debug!("Adding camera '{}' to pool with mount offset {:?}", label, mount);
+        self.members.retain(|m| m.label != label);
+        let latest = client.subscribe_watch();
+        self.members.push(PoolMember { label, client, mount, latest });
+    }
+
+    pub fn client(&self, label: &str) -> Option<&LimelightClient> {
+        self.members.iter().find(|m| m.label == label).map(|m| &m.client)
+    }
+
+    // The most recent result polled by that camera, None for an unknown label or before the first poll
+    pub fn get_latest(&self, label: &str) -> Option<LimelightResult> {
+        self.members.iter().find(|m| m.label == label)?.latest.borrow().clone()
+    }
+
+    // Every camera's results on one channel, tagged with the camera's label. Cameras added
+    // after this call are not included; the forwarding tasks end once the receiver is dropped.
+    pub fn subscribe(&self) -> broadcast::Receiver<(String, LimelightResult)> {
+        merge_subscriptions(self.members.iter().map(|m| (m.label.as_str(), &m.client)))
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.members.iter().map(|m| m.label.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub async fn start_all(&self) -> Result<(), LimelightError> {
+        for member in &self.members {
+            member.client.start().await?;
+        }
+        Ok(())
+    }
+
+    pub async fn stop_all(&self) {
+        for member in &self.members {
+            member.client.stop().await;
+        }
+    }
+
+    // Area-weighted (tx, ty) across every camera currently seeing a target, after
+    // applying each camera's mount offset. None when no camera has a target.
+    pub fn fused_target_angle(&self) -> Option<(f64, f64)> {
+        let mut weight_sum = 0.0;
+        let mut tx_sum = 0.0;
+        let mut ty_sum = 0.0;
+
+        for member in &self.members {
+            let latest = member.latest.borrow();
+            let Some(result) = latest.as_ref() else { continue };
+            if !result.valid() {
+                continue;
+            }
+            let (Some(tx), Some(ty), Some(ta)) = (result.tx, result.ty, result.ta) else { continue };
+            if ta <= 0.0 {
+                continue;
+            }
+            weight_sum += ta;
+            tx_sum += ta * (tx + member.mount.yaw_degrees);
+            ty_sum += ta * (ty + member.mount.pitch_degrees);
+        }
+
+        if weight_sum > 0.0 {
+            Some((tx_sum / weight_sum, ty_sum / weight_sum))
+        } else {
+            None
+        }
+    }
+}
+
+// One forwarding task per camera, each ending once the returned receiver is dropped
+pub(crate) fn merge_subscriptions<'a>(
+    cameras: impl Iterator<Item = (&'a str, &'a LimelightClient)>,
+) -> broadcast::Receiver<(String, LimelightResult)> {
+    let (tx, rx) = broadcast::channel(MERGED_CHANNEL_CAPACITY);
+    for (label, client) in cameras {
+        let label = label.to_string();
+        let mut results = client.subscribe();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            loop {
+                match results.recv().await {
+                    Ok(result) => {
+                        if tx.send((label.clone(), result)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Merged subscription for '{}' lagged, skipped {} results", label, skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            tracing::debug!("Merged subscription task for '{}' stopped", label);
+        });
+    }
+    rx
+}
@@ -0,0 +1,235 @@
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pose3d {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub roll: f64,
+    pub pitch: f64,
+    pub yaw: f64,
+}
+
+impl Pose3d {
+    pub fn new(x: f64, y: f64, z: f64, roll: f64, pitch: f64, yaw: f64) -> Self {
+        Self { x, y, z, roll, pitch, yaw }
+    }
+
+    // Botpose-style arrays are [x, y, z, roll, pitch, yaw, ...] in meters/degrees
+    pub fn from_array(values: &[f64]) -> Option<Self> {
+        if values.len() < 6 {
+            return None;
+        }
+        Some(Self::new(values[0], values[1], values[2], values[3], values[4], values[5]))
+    }
+
+    // Heading in degrees, counter-clockwise positive; this is the yaw component
+    pub fn heading_degrees(&self) -> f64 {
+        self.yaw
+    }
+
+    // Drops z, roll and pitch
+    pub fn to_pose2d(&self) -> Pose2d {
+        Pose2d { x: self.x, y: self.y, heading: self.yaw }
+    }
+
+    // Classic NetworkTables botpose: [x, y, z, roll, pitch, yaw, latency_ms]
+    pub fn to_nt_array(&self, latency_ms: f64) -> [f64; 7] {
+        [self.x, self.y, self.z, self.roll, self.pitch, self.yaw, latency_ms]
+    }
+
+    // Accepts the 6-element legacy form as well; anything past the pose is ignored
+    pub fn from_nt_array(values: &[f64]) -> Option<Self> {
+        Self::from_array(values)
+    }
+
+    // Row-major 4x4 homogeneous transform, translation in meters
+    pub fn from_transform_matrix(m: &[f64]) -> Option<Self> {
+        if m.len() < 16 {
+            return None;
+        }
+        let rotation = [[m[0], m[1], m[2]], [m[4], m[5], m[6]], [m[8], m[9], m[10]]];
+        Some(Self::from_rotation_matrix([m[3], m[7], m[11]], &rotation))
+    }
+
+    // Which alliance's half of the field this blue-origin position is in; the centerline counts as red
+    pub fn field_half(&self, field_length: f64) -> FieldHalf {
+        if self.x < field_length / 2.0 {
+            FieldHalf::Blue
+        } else {
+            FieldHalf::Red
+        }
+    }
+
+    // Pose of `other` expressed in this pose's frame (self^-1 * other)
+    pub fn relative_to(&self, other: &Pose3d) -> Pose3d {
+        let r_self = self.rotation_matrix();
+        let r_other = other.rotation_matrix();
+        let delta = [other.x - self.x, other.y - self.y, other.z - self.z];
+
+        // R^-1 = R^T for rotation matrices
+        let mut translation = [0.0; 3];
+        let mut rotation = [[0.0; 3]; 3];
+        for i in 0..3 {
+            translation[i] = (0..3).map(|k| r_self[k][i] * delta[k]).sum();
+            for j in 0..3 {
+                rotation[i][j] = (0..3).map(|k| r_self[k][i] * r_other[k][j]).sum();
+            }
+        }
+        Self::from_rotation_matrix(translation, &rotation)
+    }
+
+    // Rotation applied as yaw (Z), then pitch (Y), then roll (X)
+    fn rotation_matrix(&self) -> [[f64; 3]; 3] {
+        let (sr, cr) = self.roll.to_radians().sin_cos();
+        let (sp, cp) = self.pitch.to_radians().sin_cos();
+        let (sy, cy) = self.yaw.to_radians().sin_cos();
+        [
+            [cy * cp, cy * sp * sr - sy * cr, cy * sp * cr + sy * sr],
+            [sy * cp, sy * sp * sr + cy * cr, sy * sp * cr - cy * sr],
+            [-sp, cp * sr, cp * cr],
+        ]
+    }
+
+    fn from_rotation_matrix(translation: [f64; 3], r: &[[f64; 3]; 3]) -> Pose3d {
+        Pose3d {
+            x: translation[0],
+            y: translation[1],
+            z: translation[2],
+            roll: r[2][1].atan2(r[2][2]).to_degrees(),
+            pitch: (-r[2][0]).clamp(-1.0, 1.0).asin().to_degrees(),
+            yaw: r[1][0].atan2(r[0][0]).to_degrees(),
+        }
+    }
+}
+
+// Planar pose: x and y in meters, heading in degrees counter-clockwise positive
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pose2d {
+    pub x: f64,
+    pub y: f64,
+    pub heading: f64,
+}
+
+impl Pose2d {
+    pub fn new(x: f64, y: f64, heading: f64) -> Self {
+        Self { x, y, heading }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldHalf {
+    Blue,
+    Red,
+}
+
+// A pose handed out by the client; `stale` marks the configured fallback rather than a fresh vision pose
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoseReading {
+    pub pose: Pose3d,
+    pub stale: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PoseOrigin {
+    Field,
+    WpiBlue,
+    WpiRed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MegaTagVersion {
+    MegaTag1,
+    MegaTag2,
+}
+
+// Translation is averaged linearly, angles circularly so 179 and -179 average to 180
+pub fn average_poses(poses: &[Pose3d]) -> Option<Pose3d> {
+    if poses.is_empty() {
+        return None;
+    }
+    let n = poses.len() as f64;
+    Some(Pose3d {
+        x: poses.iter().map(|p| p.x).sum::<f64>() / n,
+        y: poses.iter().map(|p| p.y).sum::<f64>() / n,
+        z: poses.iter().map(|p| p.z).sum::<f64>() / n,
+        roll: circular_mean_degrees(poses.iter().map(|p| p.roll)),
+        pitch: circular_mean_degrees(poses.iter().map(|p| p.pitch)),
+        yaw: circular_mean_degrees(poses.iter().map(|p| p.yaw)),
+    })
+}
+
+// Measurement standard deviations for a pose estimator: meters for x/y, degrees for yaw
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PoseStdDevs {
+    pub x: f64,
+    pub y: f64,
+    pub yaw: f64,
+}
+
+// Scales `base` by 1 + avg_dist^2 / tag_count: trust falls off with distance and grows with
+// every extra tag, but never exceeds `base`, so a close tag can't yield zero std devs. With
+// no tags the measurement carries no information and every component is infinite.
+pub fn scaled_std_devs(base: &PoseStdDevs, avg_dist: f64, tag_count: i32) -> PoseStdDevs {
+    if tag_count <= 0 {
+        return PoseStdDevs { x: f64::INFINITY, y: f64::INFINITY, yaw: f64::INFINITY };
+    }
+    let scale = 1.0 + avg_dist.max(0.0).powi(2) / tag_count as f64;
+    PoseStdDevs { x: base.x * scale, y: base.y * scale, yaw: base.yaw * scale }
+}
+
+// RMS distance of the samples from `mean`, in meters
+pub fn translation_spread(poses: &[Pose3d], mean: &Pose3d) -> f64 {
+    if poses.is_empty() {
+        return 0.0;
+    }
+    let sum_sq: f64 = poses
+        .iter()
+        .map(|p| (p.x - mean.x).powi(2) + (p.y - mean.y).powi(2) + (p.z - mean.z).powi(2))
+        .sum();
+    (sum_sq / poses.len() as f64).sqrt()
+}
+
+// Circular standard deviation of the yaw samples, in degrees
+pub fn yaw_spread_degrees(poses: &[Pose3d]) -> f64 {
+    if poses.is_empty() {
+        return 0.0;
+    }
+    let n = poses.len() as f64;
+    let (sin, cos) = poses.iter().fold((0.0, 0.0), |(s, c), p| {
+        let yaw = p.yaw.to_radians();
+        (s + yaw.sin(), c + yaw.cos())
+    });
+    let resultant = ((sin / n).powi(2) + (cos / n).powi(2)).sqrt().min(1.0);
+    (-2.0 * resultant.ln()).sqrt().to_degrees()
+}
+
+// Pose at time `t` between samples `a` (at `ta`) and `b` (at `tb`). Translation is linear and
+// each angle takes the shortest way around; `t` outside [ta, tb] is clamped to the nearer sample.
+pub fn interpolate_pose(a: &Pose3d, ta: f64, b: &Pose3d, tb: f64, t: f64) -> Pose3d {
+    if tb == ta {
+        return *a;
+    }
+    let f = ((t - ta) / (tb - ta)).clamp(0.0, 1.0);
+    let lerp = |from: f64, to: f64| from + (to - from) * f;
+    Pose3d {
+        x: lerp(a.x, b.x),
+        y: lerp(a.y, b.y),
+        z: lerp(a.z, b.z),
+        roll: lerp_angle_degrees(a.roll, b.roll, f),
+        pitch: lerp_angle_degrees(a.pitch, b.pitch, f),
+        yaw: lerp_angle_degrees(a.yaw, b.yaw, f),
+    }
+}
+
+// Result is wrapped to [-180, 180)
+fn lerp_angle_degrees(from: f64, to: f64, f: f64) -> f64 {
+    let delta = (to - from + 180.0).rem_euclid(360.0) - 180.0;
+    (from + delta * f + 180.0).rem_euclid(360.0) - 180.0
+}
+
+fn circular_mean_degrees(angles: impl Iterator<Item = f64>) -> f64 {
+    let (sin, cos) = angles.fold((0.0, 0.0), |(s, c), angle: f64| {
+        let radians = angle.to_radians();
+        (s + radians.sin(), c + radians.cos())
+    });
+    sin.atan2(cos).to_degrees()
+}
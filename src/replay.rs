@@ -0,0 +1,95 @@
+use crate::{LimelightClient, LimelightError, LimelightResult};
+use tokio::sync::{broadcast, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+
+// A result plus when it arrived, relative to the start of the recording
+#[derive(Debug, Clone)]
+pub struct RecordedFrame {
+    pub offset: Duration,
+    pub result: LimelightResult,
+}
+
+pub struct SessionRecorder {
+    stop_tx: oneshot::Sender<()>,
+    task: JoinHandle<Vec<RecordedFrame>>,
+}
+
+impl SessionRecorder {
+    pub fn start(client: &LimelightClient) -> Self {
+        let results = client.subscribe();
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let task = tokio::spawn(Self::record(results, stop_rx));
+        Self { stop_tx, task }
+    }
+
+    async fn record(
+        mut results: broadcast::Receiver<LimelightResult>,
+        mut stop_rx: oneshot::Receiver<()>,
+    ) -> Vec<RecordedFrame> {
+        let started = Instant::now();
+        let mut frames = Vec::new();
+        loop {
+            tokio::select! {
+                received = results.recv() => match received {
+                    Ok(result) => frames.push(RecordedFrame { offset: started.elapsed(), result }),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Session recorder lagged, {} results were not recorded", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = &mut stop_rx => break,
+            }
+        }
+        tracing::debug!("Recorded {} frames over {:?}", frames.len(), started.elapsed());
+        frames
+    }
+
+    pub async fn finish(self) -> Result<Vec<RecordedFrame>, LimelightError> {
+        let _ = self.stop_tx.send(());
+        Ok(self.task.await?)
+    }
+}
+
+// Replays frames on a single broadcast channel in recording order. The channel holds the
+// whole session, so subscribers never lag and every one sees the identical sequence.
+pub struct SessionReplayer {
+    frames: Vec<RecordedFrame>,
+    strict_ordering: bool,
+    tx: broadcast::Sender<LimelightResult>,
+}
+
+impl SessionReplayer {
+    pub fn new(mut frames: Vec<RecordedFrame>) -> Self {
+        // Stable, so frames sharing an offset keep their recorded order
+        frames.sort_by_key(|frame| frame.offset);
+        let (tx, _) = broadcast::channel(frames.len().max(1));
+        Self { frames, strict_ordering: false, tx }
+    }
+
+    // When enabled, replay sleeps to reproduce the original inter-frame timing instead of
+    // delivering as fast as possible
+    pub fn strict_ordering(mut self, enabled: bool) -> Self {
+        self.strict_ordering = enabled;
+        self
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LimelightResult> {
+        self.tx.subscribe()
+    }
+
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    pub async fn run(&self) {
+        tracing::debug!("Replaying {} frames (strict_ordering={})", self.frames.len(), self.strict_ordering);
+        let started = Instant::now();
+        for frame in &self.frames {
+            if self.strict_ordering {
+                tokio::time::sleep_until(started + frame.offset).await;
+            }
+            let _ = self.tx.send(frame.result.clone());
+        }
+    }
+}
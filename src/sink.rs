@@ -0,0 +1,173 @@
+use crate::client::unix_seconds;
+use crate::{LimelightClient, LimelightError, LimelightResult};
+use std::io::Write;
+use tokio::sync::{broadcast, oneshot};
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CsvColumn {
+    Timestamp,
+    Tx,
+    Ty,
+    Ta,
+    Valid,
+    BotposeX,
+    BotposeY,
+    BotposeZ,
+    BotposeRoll,
+    BotposePitch,
+    BotposeYaw,
+    TagCount,
+    LatencyMs,
+    PipelineId,
+}
+
+impl CsvColumn {
+    pub const DEFAULT: [CsvColumn; 13] = [
+        CsvColumn::Timestamp,
+        CsvColumn::Tx,
+        CsvColumn::Ty,
+        CsvColumn::Ta,
+        CsvColumn::Valid,
+        CsvColumn::BotposeX,
+        CsvColumn::BotposeY,
+        CsvColumn::BotposeZ,
+        CsvColumn::BotposeRoll,
+        CsvColumn::BotposePitch,
+        CsvColumn::BotposeYaw,
+        CsvColumn::TagCount,
+        CsvColumn::LatencyMs,
+    ];
+
+    pub fn header(&self) -> &'static str {
+        match self {
+            CsvColumn::Timestamp => "timestamp",
+            CsvColumn::Tx => "tx",
+            CsvColumn::Ty => "ty",
+            CsvColumn::Ta => "ta",
+            CsvColumn::Valid => "v",
+            CsvColumn::BotposeX => "botpose_x",
+            CsvColumn::BotposeY => "botpose_y",
+            CsvColumn::BotposeZ => "botpose_z",
+            CsvColumn::BotposeRoll => "botpose_roll",
+            CsvColumn::BotposePitch => "botpose_pitch",
+            CsvColumn::BotposeYaw => "botpose_yaw",
+            CsvColumn::TagCount => "tagcount",
+            CsvColumn::LatencyMs => "latency_ms",
+            CsvColumn::PipelineId => "pipeline_id",
+        }
+    }
+
+    fn value(&self, result: &LimelightResult, timestamp: f64) -> Option<f64> {
+        let botpose = |index: usize| result.botpose.as_ref().and_then(|p| p.get(index).copied());
+        match self {
+            CsvColumn::Timestamp => Some(timestamp),
+            CsvColumn::Tx => result.tx,
+            CsvColumn::Ty => result.ty,
+            CsvColumn::Ta => result.ta,
+            CsvColumn::Valid => result.v,
+            CsvColumn::BotposeX => botpose(0),
+            CsvColumn::BotposeY => botpose(1),
+            CsvColumn::BotposeZ => botpose(2),
+            CsvColumn::BotposeRoll => botpose(3),
+            CsvColumn::BotposePitch => botpose(4),
+            CsvColumn::BotposeYaw => botpose(5),
+            CsvColumn::TagCount => result.botpose_tagcount.map(f64::from),
+            CsvColumn::LatencyMs => result.total_latency_ms(),
+            CsvColumn::PipelineId => result.pipeline_id.map(f64::from),
+        }
+    }
+}
+
+// Writes one flattened row per result; missing values are left as empty fields
+pub struct CsvSink<W: Write> {
+    writer: W,
+    columns: Vec<CsvColumn>,
+    header_written: bool,
+}
+
+impl<W: Write> CsvSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self::with_columns(writer, CsvColumn::DEFAULT.to_vec())
+    }
+
+    pub fn with_columns(writer: W, columns: Vec<CsvColumn>) -> Self {
+        Self {
+            writer,
+            columns,
+            header_written: false,
+        }
+    }
+
+    pub fn write_result(&mut self, result: &LimelightResult) -> Result<(), LimelightError> {
+        self.write_row(result, unix_seconds())
+    }
+
+    pub fn write_row(&mut self, result: &LimelightResult, timestamp: f64) -> Result<(), LimelightError> {
+        if !self.header_written {
+            let header: Vec<&str> = self.columns.iter().map(CsvColumn::header).collect();
+            writeln!(self.writer, "{}", header.join(","))?;
+            self.header_written = true;
+        }
+        let row: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| column.value(result, timestamp).map(|v| v.to_string()).unwrap_or_default())
+            .collect();
+        writeln!(self.writer, "{}", row.join(","))?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), LimelightError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn close(mut self) -> Result<W, LimelightError> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write + Send + 'static> CsvSink<W> {
+    // Records every result the client broadcasts until the handle is closed
+    pub fn spawn(self, client: &LimelightClient) -> CsvSinkHandle<W> {
+        let results = client.subscribe();
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let task = tokio::spawn(self.record(results, stop_rx));
+        CsvSinkHandle { stop_tx, task }
+    }
+
+    async fn record(
+        mut self,
+        mut results: broadcast::Receiver<LimelightResult>,
+        mut stop_rx: oneshot::Receiver<()>,
+    ) -> Result<W, LimelightError> {
+        loop {
+            tokio::select! {
+                received = results.recv() => match received {
+                    Ok(result) => self.write_result(&result)?,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("CSV sink lagged, {} results were not recorded", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = &mut stop_rx => break,
+            }
+        }
+        self.close()
+    }
+}
+
+pub struct CsvSinkHandle<W> {
+    stop_tx: oneshot::Sender<()>,
+    task: JoinHandle<Result<W, LimelightError>>,
+}
+
+impl<W> CsvSinkHandle<W> {
+    // Stops recording, flushes, and hands back the writer
+    pub async fn close(self) -> Result<W, LimelightError> {
+        let _ = self.stop_tx.send(());
+        self.task.await?
+    }
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "blocking")]
+
+mod common;
+
+use common::{MockResponse, MockServer};
+use limelightlib_rust::BlockingLimelightClient;
+use std::time::{Duration, Instant};
+
+const RESULTS_JSON: &str = r#"{"pipeline_id": 3, "v": 1, "tx": 2.0}"#;
+
+#[test]
+fn blocking_client_fetches_and_polls_without_a_runtime() {
+    let server_runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = server_runtime.block_on(MockServer::start());
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    server.mock("pipeline-switch", MockResponse::status(200));
+
+    let client = BlockingLimelightClient::new(server.config()).unwrap();
+    assert_eq!(client.fetch_once().unwrap().pipeline_id, Some(3));
+    assert!(client.get_latest_result().is_none());
+    assert!(client.switch_pipeline(1).unwrap());
+
+    client.start().unwrap();
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while client.get_latest_result().is_none() {
+        assert!(Instant::now() < deadline, "no result polled");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    client.stop();
+    assert_eq!(server.requests_to("pipeline-switch")[0].path, "pipeline-switch?index=1");
+}
+
+#[tokio::test]
+async fn dropping_inside_a_runtime_stops_polling() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+
+    let client = BlockingLimelightClient::new(server.config()).unwrap();
+    let inner = client.client().clone();
+    inner.start().await.unwrap();
+    tokio::time::timeout(Duration::from_secs(2), inner.next_result()).await.unwrap().unwrap();
+
+    drop(client);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let polled = server.requests_to("results").len();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(server.requests_to("results").len(), polled);
+    assert!(matches!(inner.next_result().await, Err(limelightlib_rust::LimelightError::NotRunning)));
+}
@@ -0,0 +1,1538 @@
+mod common;
+
+use common::{MockResponse, MockServer};
+use limelightlib_rust::{ChannelMode, CommandOutcome, ConnectionState, LedMode, LimelightClient, LimelightConfig, LimelightError, ManualClock, MegaTagVersion, PipelineType, Pose3d, PoseOrigin, PoseReading, SlowSubscriberPolicy, SlowdownReason, StreamMode, TargetEvent};
+use tokio::time::{timeout, Duration};
+
+const RESULTS_JSON: &str = r#"{
+    "pipeline_id": 2,
+    "tx": 1.5,
+    "ty": -2.25,
+    "ta": 0.8,
+    "v": 1,
+    "ts": 1000.0,
+    "botpose": [1.0, 2.0, 0.0, 0.0, 0.0, 90.0, 25.0, 2, 0.5, 3.0, 0.1],
+    "botpose_tagcount": 2,
+    "Fiducial": [
+        {"fID": 7, "fam": "36H11C", "ta": 0.5, "tx": 1.0, "ty": 2.0},
+        {"fID": 8, "fam": "36H11C", "ta": 0.9, "tx": -1.0, "ty": 0.5}
+    ]
+}"#;
+
+#[tokio::test]
+async fn start_broadcasts_parsed_results() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+
+    let client = LimelightClient::new(server.config());
+    let mut results = client.subscribe();
+    client.start().await.unwrap();
+
+    let result = timeout(Duration::from_secs(2), results.recv())
+        .await
+        .expect("no result broadcast")
+        .unwrap();
+    client.stop().await;
+
+    assert_eq!(result.tx, Some(1.5));
+    assert_eq!(result.ty, Some(-2.25));
+    assert_eq!(result.pipeline_id, Some(2));
+    assert_eq!(result.fiducial.len(), 2);
+    assert_eq!(result.fiducial[1].f_id, Some(8));
+    assert!(client.get_latest_result().await.is_some());
+}
+
+#[tokio::test]
+async fn manual_clock_controls_poll_count() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+
+    let clock = ManualClock::new();
+    let client = LimelightClient::with_clock(server.config(), clock.clone());
+    let mut results = client.subscribe();
+    client.start().await.unwrap();
+
+    clock.advance(3);
+    for _ in 0..3 {
+        timeout(Duration::from_secs(2), results.recv()).await.unwrap().unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(server.requests_to("results").len(), 3);
+
+    client.stop().await;
+    clock.advance(1);
+}
+
+#[tokio::test]
+async fn switch_pipeline_posts_index() {
+    let server = MockServer::start().await;
+    server.mock("pipeline-switch", MockResponse::status(200));
+
+    let client = LimelightClient::new(server.config());
+    assert!(client.switch_pipeline(3).await.unwrap());
+
+    let requests = server.requests_to("pipeline-switch");
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "pipeline-switch?index=3");
+}
+
+#[tokio::test]
+async fn command_reports_failure_status() {
+    let server = MockServer::start().await;
+    server.mock("reload-pipeline", MockResponse::status(500));
+
+    let client = LimelightClient::new(server.config());
+    assert!(!client.reload_pipeline().await.unwrap());
+}
+
+#[tokio::test]
+async fn get_json_parses_status() {
+    let server = MockServer::start().await;
+    server.mock("status", MockResponse::json(r#"{"cpu": 45.0, "fps": 90}"#));
+
+    let client = LimelightClient::new(server.config());
+    let status = client.get_status().await.unwrap();
+    assert_eq!(status["fps"], 90);
+}
+
+#[tokio::test]
+async fn malformed_json_maps_to_json_error() {
+    let server = MockServer::start().await;
+    server.mock("status", MockResponse::json("{not json"));
+
+    let client = LimelightClient::new(server.config());
+    let err = client.get_status().await.unwrap_err();
+    assert!(matches!(err, LimelightError::JsonError(_)), "got {:?}", err);
+}
+
+#[tokio::test]
+async fn oversized_response_is_rejected() {
+    let server = MockServer::start().await;
+    server.mock("status", MockResponse::json(&format!("[{}0]", "0,".repeat(1024))));
+
+    let client = LimelightClient::new(LimelightConfig {
+        max_response_bytes: 512,
+        ..server.config()
+    });
+    let err = client.get_status().await.unwrap_err();
+    assert!(matches!(err, LimelightError::ResponseTooLarge(512)), "got {:?}", err);
+}
+
+#[tokio::test]
+async fn unreachable_device_maps_to_http_error() {
+    let server = MockServer::start().await;
+    let config = server.config();
+    drop(server);
+
+    // Nothing listens on port 1, so the connection is refused
+    let client = LimelightClient::new(LimelightConfig { port: 1, ..config });
+    let err = client.get_status().await.unwrap_err();
+    assert!(matches!(err, LimelightError::HttpError(_)), "got {:?}", err);
+}
+
+#[tokio::test]
+async fn throttled_subscriber_limits_rate() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+
+    let client = LimelightClient::new(server.config());
+    let mut throttled = client.subscribe_throttled(5.0).unwrap();
+    client.start().await.unwrap();
+
+    let mut received = 0;
+    let window = tokio::time::sleep(Duration::from_millis(600));
+    tokio::pin!(window);
+    loop {
+        tokio::select! {
+            Some(_) = throttled.recv() => received += 1,
+            _ = &mut window => break,
+        }
+    }
+    client.stop().await;
+
+    assert!((1..=4).contains(&received), "received {} results in 600ms at 5Hz", received);
+    assert!(client.subscribe_throttled(0.0).is_err());
+}
+
+#[tokio::test]
+async fn ensure_pipeline_skips_active_index() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    server.mock("pipeline-switch", MockResponse::status(200));
+
+    let client = LimelightClient::new(server.config());
+    assert!(!client.ensure_pipeline(2).await.unwrap());
+    assert!(server.requests_to("pipeline-switch").is_empty());
+
+    assert!(client.ensure_pipeline(1).await.unwrap());
+    assert_eq!(server.requests_to("pipeline-switch")[0].path, "pipeline-switch?index=1");
+}
+
+#[tokio::test]
+async fn rejected_gzip_pipeline_upload_falls_back_to_plain() {
+    let server = MockServer::start().await;
+    server.mock("upload-pipeline", MockResponse::status(415));
+
+    let client = LimelightClient::new(LimelightConfig {
+        compress_pipeline_uploads: true,
+        ..server.config()
+    });
+    let pipeline = serde_json::json!({ "exposure": 3300.0, "sensor_gain": 15.0 });
+    assert!(!client.upload_pipeline(pipeline.clone(), Some(1)).await.unwrap());
+
+    let requests = server.requests_to("upload-pipeline");
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].headers.get("content-encoding").map(String::as_str), Some("gzip"));
+    assert!(!requests[1].headers.contains_key("content-encoding"));
+    assert_eq!(requests[1].body, serde_json::to_vec(&pipeline).unwrap());
+}
+
+#[tokio::test]
+async fn array_results_are_broadcast_individually_and_as_batch() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(r#"[{"tx": 1.0}, {"tx": 2.0}]"#));
+
+    let client = LimelightClient::new(server.config());
+    let mut results = client.subscribe();
+    let mut batches = client.subscribe_batches();
+    client.start().await.unwrap();
+
+    let batch = timeout(Duration::from_secs(2), batches.recv()).await.unwrap().unwrap();
+    let first = results.recv().await.unwrap();
+    let second = results.recv().await.unwrap();
+    client.stop().await;
+
+    assert_eq!(batch.len(), 2);
+    assert_eq!(first.tx, Some(1.0));
+    assert_eq!(second.tx, Some(2.0));
+}
+
+#[tokio::test]
+async fn parse_failures_are_reported_as_slowdown_reason() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json("{not json"));
+
+    let clock = ManualClock::new();
+    let client = LimelightClient::with_clock(server.config(), clock.clone());
+    client.start().await.unwrap();
+    clock.advance(2);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    client.stop().await;
+    clock.advance(1);
+
+    let metrics = client.poll_metrics().await;
+    assert_eq!(metrics.successes, 0);
+    assert!(metrics.parse_errors >= 1);
+    assert!(!client.is_keeping_up().await);
+    assert_eq!(client.slowdown_reason().await, Some(SlowdownReason::ParseErrors));
+}
+
+#[tokio::test]
+async fn configured_headers_are_sent() {
+    let server = MockServer::start().await;
+    server.mock("status", MockResponse::json(r#"{"cpu": 1}"#));
+
+    let mut config = server.config();
+    config.headers.insert("X-Gateway-Token".into(), "secret".into());
+    config.user_agent = Some("team-1234-robot".into());
+    let client = LimelightClient::new(config);
+    client.get_status().await.unwrap();
+
+    let request = &server.requests_to("status")[0];
+    assert_eq!(request.headers.get("x-gateway-token").map(String::as_str), Some("secret"));
+    assert_eq!(request.headers.get("user-agent").map(String::as_str), Some("team-1234-robot"));
+}
+
+#[tokio::test]
+async fn next_result_requires_running_client() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let client = LimelightClient::new(server.config());
+
+    assert!(matches!(client.next_result().await, Err(LimelightError::NotRunning)));
+
+    client.start().await.unwrap();
+    let result = timeout(Duration::from_secs(2), client.next_result()).await.unwrap().unwrap();
+    client.stop().await;
+    assert_eq!(result.pipeline_id, Some(2));
+}
+
+#[tokio::test]
+async fn rate_limited_poll_honors_retry_after() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::status(429).with_header("Retry-After", "5"));
+
+    let client = LimelightClient::new(server.config());
+    client.start().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    client.stop().await;
+    // The 5 s pause ends as soon as the client stops
+    timeout(Duration::from_secs(1), client.join()).await.expect("join waited out the rate-limit pause");
+
+    assert_eq!(server.requests_to("results").len(), 1);
+    let metrics = client.poll_metrics().await;
+    assert_eq!(metrics.rate_limited, 1);
+    assert_eq!(client.slowdown_reason().await, Some(SlowdownReason::RateLimited));
+}
+
+#[tokio::test]
+async fn orientation_updates_are_tracked_for_megatag2() {
+    let server = MockServer::start().await;
+    server.mock("update-robotorientation", MockResponse::status(200));
+    let client = LimelightClient::new(server.config());
+
+    assert!(!client.megatag2_orientation_fresh(Duration::from_secs(1)).await);
+    assert!(client.update_robot_orientation(90.0).await.unwrap());
+    assert!(client.megatag2_orientation_fresh(Duration::from_secs(1)).await);
+
+    assert!(client.reset_robot_orientation().await.unwrap());
+    assert!(!client.megatag2_orientation_fresh(Duration::from_secs(1)).await);
+    let reset = server.requests_to("update-robotorientation").pop().unwrap();
+    assert_eq!(reset.body, b"[0.0,0.0,0.0,0.0,0.0,0.0]");
+}
+
+#[tokio::test]
+async fn probe_endpoints_records_supported_set() {
+    let server = MockServer::start().await;
+    server.mock("status", MockResponse::json("{}"));
+    server.mock("hwreport", MockResponse::json("{}"));
+    server.mock("cal-eeprom", MockResponse::status(500));
+
+    let support = LimelightClient::new(server.config()).probe_endpoints().await;
+    assert!(support.is_supported("status"));
+    assert!(support.is_supported("hwreport"));
+    assert!(!support.is_supported("cal-eeprom"));
+    assert!(!support.is_supported("getsnapsscriptnames"));
+    assert_eq!(support.supported().collect::<Vec<_>>(), vec!["hwreport", "status"]);
+}
+
+#[tokio::test]
+async fn target_arrays_are_capped() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+
+    let mut config = server.config();
+    config.max_targets_per_type = 1;
+    let client = LimelightClient::new(config);
+    let mut results = client.subscribe();
+    client.start().await.unwrap();
+    let result = timeout(Duration::from_secs(2), results.recv()).await.unwrap().unwrap();
+    client.stop().await;
+
+    assert_eq!(result.fiducial.len(), 1);
+    assert_eq!(result.fiducial[0].f_id, Some(7));
+}
+
+#[tokio::test]
+async fn device_time_comes_from_result_ts() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let client = LimelightClient::new(server.config());
+
+    assert_eq!(client.get_device_time().await.unwrap(), 1.0);
+    let offset = client.estimate_clock_offset().await.unwrap();
+    assert!(offset < 0.0);
+    assert_eq!(server.requests_to("results").len(), 6);
+}
+
+#[tokio::test]
+async fn device_time_adds_reported_latency_to_capture_ts() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(r#"{"ts": 1000.0, "cl": 20.0, "tl": 30.0}"#));
+    let client = LimelightClient::new(server.config());
+
+    assert!((client.get_device_time().await.unwrap() - 1.05).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn target_events_fire_on_validity_edges() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let client = LimelightClient::new(server.config());
+    let mut events = client.subscribe_target_events();
+    client.start().await.unwrap();
+
+    let wait = Duration::from_secs(2);
+    assert_eq!(timeout(wait, events.recv()).await.unwrap().unwrap(), TargetEvent::Acquired);
+    server.mock("results", MockResponse::json(r#"{"v": 0}"#));
+    assert_eq!(timeout(wait, events.recv()).await.unwrap().unwrap(), TargetEvent::Lost);
+    client.stop().await;
+    assert!(events.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn backpressure_policy_keeps_slow_subscriber_from_lagging() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+
+    let mut config = server.config();
+    config.poll_interval_ms = 1;
+    config.slow_subscriber_policy = SlowSubscriberPolicy::Backpressure { timeout: Duration::from_secs(10) };
+    let client = LimelightClient::new(config);
+    let mut results = client.subscribe();
+    client.start().await.unwrap();
+
+    // Long enough for the loop to fill the 100-slot ring and stall on it
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    for _ in 0..150 {
+        timeout(Duration::from_secs(2), results.recv()).await.unwrap().expect("subscriber lagged");
+    }
+    client.stop().await;
+}
+
+#[tokio::test]
+async fn set_poll_rate_restart_leaves_one_poller() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+
+    let mut config = server.config();
+    config.poll_interval_ms = 100;
+    let client = LimelightClient::new(config);
+    client.start().await.unwrap();
+    for _ in 0..3 {
+        client.set_poll_rate(100).await.unwrap();
+    }
+
+    let before = server.requests_to("results").len();
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+    let polled = server.requests_to("results").len() - before;
+    client.stop().await;
+    // One poller makes about 10 requests a second; four stacked ones would make about 40
+    assert!((5..=15).contains(&polled), "polled {} times", polled);
+}
+
+#[tokio::test]
+async fn poll_loop_gives_up_after_max_restarts() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+
+    let mut config = server.config();
+    config.max_poll_restarts = 2;
+    let client = LimelightClient::new(config);
+    let panics = Arc::new(AtomicUsize::new(0));
+    let counter = panics.clone();
+    client
+        .set_on_result(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            panic!("callback failure");
+        })
+        .await;
+    client.start().await.unwrap();
+
+    // Without stop(), join() only returns once the supervisor stops restarting
+    timeout(Duration::from_secs(5), client.join()).await.unwrap();
+    assert_eq!(panics.load(Ordering::SeqCst), 3);
+    assert!(matches!(client.next_result().await, Err(LimelightError::NotRunning)));
+}
+
+#[tokio::test]
+async fn backward_ts_counts_as_discontinuity() {
+    let server = MockServer::start().await;
+    // Only 200 -> 50 goes backward; a repeat or a frame without ts doesn't count
+    server.mock_cycle(
+        "results",
+        ["100.0", "100.0", "200.0", "null", "50.0"].iter().map(|ts| MockResponse::json(&format!(r#"{{"ts": {}}}"#, ts))).collect(),
+    );
+    let client = LimelightClient::new(server.config());
+    let mut results = client.subscribe();
+    client.start().await.unwrap();
+    for _ in 0..10 {
+        timeout(Duration::from_secs(2), results.recv()).await.unwrap().unwrap();
+    }
+    client.stop().await;
+    client.join().await;
+
+    let polled = server.requests_to("results").len() as u64;
+    assert!(polled >= 10);
+    assert_eq!(client.ts_discontinuity_count().await, (polled + 1) / 5);
+}
+
+#[tokio::test]
+async fn pose_stream_back_dates_by_reported_latency() {
+    use futures_util::StreamExt;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let server = MockServer::start().await;
+    server.mock_cycle(
+        "results",
+        vec![
+            MockResponse::json(r#"{"botpose": [1.0, 2.0, 0.0, 0.0, 0.0, 90.0], "botpose_tagcount": 1, "cl": 200.0, "tl": 300.0}"#),
+            MockResponse::json(r#"{"botpose": [9.0, 9.0, 0.0, 0.0, 0.0, 0.0], "botpose_tagcount": 0, "cl": 200.0, "tl": 300.0}"#),
+        ],
+    );
+    let client = LimelightClient::new(server.config());
+    let poses = client.pose_stream(PoseOrigin::Field, MegaTagVersion::MegaTag1);
+    futures_util::pin_mut!(poses);
+    let now = || SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs_f64();
+
+    let before = now();
+    client.start().await.unwrap();
+    for _ in 0..4 {
+        let (pose, timestamp) = timeout(Duration::from_secs(2), poses.next()).await.unwrap().unwrap();
+        assert_eq!((pose.x, pose.y), (1.0, 2.0), "tagless frame leaked into the stream");
+        assert!(timestamp >= before - 0.5 && timestamp <= now() - 0.5, "timestamp {} not back-dated by 500ms", timestamp);
+    }
+    client.stop().await;
+}
+
+#[tokio::test]
+async fn on_result_sees_each_result_until_cleared() {
+    use std::sync::{Arc, Mutex};
+
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let client = LimelightClient::new(server.config());
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    client.set_on_result(move |result| sink.lock().unwrap().push(result.tx)).await;
+    let mut results = client.subscribe();
+    client.start().await.unwrap();
+
+    // The callback runs before the broadcast, so it has seen every received result
+    for received in 1..=3 {
+        timeout(Duration::from_secs(2), results.recv()).await.unwrap().unwrap();
+        assert!(seen.lock().unwrap().len() >= received);
+    }
+    client.clear_on_result().await;
+    let calls = seen.lock().unwrap().len();
+    for _ in 0..3 {
+        timeout(Duration::from_secs(2), results.recv()).await.unwrap().unwrap();
+    }
+    client.stop().await;
+
+    let seen = seen.lock().unwrap();
+    assert!(seen.iter().all(|tx| *tx == Some(1.5)));
+    // At most one result already past the callback when it was cleared
+    assert!(seen.len() <= calls + 1, "callback ran {} times after clear", seen.len() - calls);
+}
+
+#[tokio::test]
+async fn fiducial_pose_ambiguity_ignores_invalid_values() {
+    let server = MockServer::start().await;
+    server.mock(
+        "results",
+        MockResponse::json(r#"{"Fiducial": [{"fID": 1, "ambiguity": 0.12}, {"fID": 2, "ambiguity": -1.0}, {"fID": 3}, {"fID": 4, "ambiguity": 1.0}, {"fID": 5, "ambiguity": 1.5}]}"#),
+    );
+    let client = LimelightClient::new(server.config());
+
+    let result = client.fetch_once().await.unwrap();
+    let ambiguities: Vec<_> = result.fiducial.iter().map(|tag| tag.pose_ambiguity()).collect();
+    assert_eq!(ambiguities, vec![Some(0.12), None, None, Some(1.0), None]);
+}
+
+#[tokio::test]
+async fn wait_for_multitag_pose_skips_frames_below_tag_count() {
+    let server = MockServer::start().await;
+    server.mock_cycle(
+        "results",
+        vec![
+            MockResponse::json(r#"{"botpose": [9.0, 9.0, 0.0, 0.0, 0.0, 0.0], "botpose_tagcount": 1}"#),
+            MockResponse::json(r#"{"botpose": [1.0, 2.0, 0.0, 0.0, 0.0, 90.0], "botpose_tagcount": 3}"#),
+        ],
+    );
+    let client = LimelightClient::new(server.config());
+    assert!(matches!(client.wait_for_multitag_pose(2, Duration::from_millis(100)).await, Err(LimelightError::NotRunning)));
+    client.start().await.unwrap();
+
+    let pose = client.wait_for_multitag_pose(2, Duration::from_secs(2)).await.unwrap();
+    assert_eq!((pose.x, pose.y, pose.yaw), (1.0, 2.0, 90.0));
+    let waited = client.wait_for_multitag_pose(4, Duration::from_millis(200)).await;
+    client.stop().await;
+    assert!(matches!(waited, Err(LimelightError::TimeoutError)), "got {:?}", waited);
+}
+
+#[tokio::test]
+async fn subscribe_watch_holds_newest_result() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+
+    let client = LimelightClient::new(server.config());
+    let mut latest = client.subscribe_watch();
+    assert!(latest.borrow().is_none());
+    client.start().await.unwrap();
+
+    timeout(Duration::from_secs(2), latest.changed()).await.unwrap().unwrap();
+    client.stop().await;
+    assert_eq!(latest.borrow().as_ref().and_then(|result| result.tx), Some(1.5));
+}
+
+#[tokio::test]
+async fn watch_mode_still_feeds_broadcast_subscribers() {
+    use futures_util::StreamExt;
+
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+
+    let mut config = server.config();
+    config.channel_mode = ChannelMode::Watch;
+    let client = LimelightClient::new(config);
+    let mut latest = client.subscribe_watch();
+    let mut results = client.subscribe();
+    client.start().await.unwrap();
+
+    let result = timeout(Duration::from_secs(2), results.recv()).await.unwrap().unwrap();
+    assert_eq!(result.pipeline_id, Some(2));
+    timeout(Duration::from_secs(2), latest.changed()).await.unwrap().unwrap();
+    let poses = client.pose_stream(PoseOrigin::Field, MegaTagVersion::MegaTag1);
+    futures_util::pin_mut!(poses);
+    let (pose, _) = timeout(Duration::from_secs(2), poses.next()).await.unwrap().unwrap();
+    assert_eq!((pose.x, pose.y), (1.0, 2.0));
+    client.stop().await;
+}
+
+#[tokio::test]
+async fn field_map_is_typed() {
+    let server = MockServer::start().await;
+    server.mock(
+        "get-fieldmap",
+        MockResponse::json(
+            r#"{"type": "frc", "fiducials": [{"id": 3, "family": "apriltag3_36h11_classic", "size": 165.1,
+                "transform": [1, 0, 0, 4.5, 0, 1, 0, 2.0, 0, 0, 1, 1.2, 0, 0, 0, 1]}]}"#,
+        ),
+    );
+    let client = LimelightClient::new(server.config());
+
+    let map = client.field_map(Some(1)).await.unwrap();
+    assert_eq!(server.requests_to("get-fieldmap")[0].path, "get-fieldmap?index=1");
+    let tag = map.fiducial(3).unwrap();
+    assert_eq!(tag.size, 165.1);
+    let pose = tag.pose().unwrap();
+    assert_eq!((pose.x, pose.y, pose.z, pose.yaw), (4.5, 2.0, 1.2, 0.0));
+}
+
+#[tokio::test]
+async fn command_history_keeps_most_recent_commands() {
+    let server = MockServer::start().await;
+    server.mock("pipeline-switch", MockResponse::status(200));
+    server.mock("delete-snapshots", MockResponse::status(500));
+
+    let mut config = server.config();
+    config.command_history_len = 2;
+    let client = LimelightClient::new(config);
+    client.switch_pipeline(1).await.unwrap();
+    client.switch_pipeline(2).await.unwrap();
+    client.delete_snapshots().await.unwrap();
+
+    let history = client.command_history().await;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].method, "POST");
+    assert_eq!(history[0].endpoint, "pipeline-switch?index=2");
+    assert_eq!(history[0].outcome, CommandOutcome::Status(200));
+    assert_eq!(history[1].method, "DELETE");
+    assert_eq!(history[1].endpoint, "delete-snapshots");
+    assert_eq!(history[1].outcome, CommandOutcome::Status(500));
+}
+
+#[tokio::test]
+async fn exposure_and_gain_are_range_checked() {
+    let server = MockServer::start().await;
+    server.mock("update-pipeline", MockResponse::status(200));
+    let client = LimelightClient::new(server.config());
+
+    for exposure in [0.0, -1.0, 33_000.5, f64::NAN] {
+        assert!(matches!(client.set_exposure(exposure, false).await, Err(LimelightError::ConfigError(_))));
+    }
+    for gain in [-0.1, 100.5, f64::NAN] {
+        assert!(matches!(client.set_gain(gain, false).await, Err(LimelightError::ConfigError(_))));
+    }
+    assert!(server.requests_to("update-pipeline").is_empty());
+
+    assert!(client.set_exposure(33_000.0, true).await.unwrap());
+    assert!(client.set_gain(0.0, false).await.unwrap());
+    let updates = server.requests_to("update-pipeline");
+    assert_eq!(updates[0].path, "update-pipeline?flush=1");
+    assert_eq!(updates[0].body, br#"{"exposure":33000.0}"#);
+    assert_eq!(updates[1].path, "update-pipeline?flush=0");
+    assert_eq!(updates[1].body, br#"{"sensor_gain":0.0}"#);
+}
+
+#[tokio::test]
+async fn hostname_and_team_number_are_validated() {
+    let server = MockServer::start().await;
+    server.mock("update-hostname", MockResponse::status(200));
+    server.mock("update-teamnumber", MockResponse::status(200));
+    let client = LimelightClient::new(server.config());
+
+    let long = "a".repeat(64);
+    for hostname in ["", "-front", "front-", "front cam", "front_cam", long.as_str()] {
+        assert!(matches!(client.set_hostname(hostname).await, Err(LimelightError::ConfigError(_))), "{:?}", hostname);
+    }
+    for team in [0, 25600] {
+        assert!(matches!(client.set_team_number(team).await, Err(LimelightError::ConfigError(_))));
+    }
+    assert!(server.requests().is_empty());
+
+    assert!(client.set_hostname("limelight-front").await.unwrap());
+    assert!(client.set_team_number(25599).await.unwrap());
+    assert_eq!(server.requests_to("update-hostname")[0].path, "update-hostname?hostname=limelight-front");
+    assert_eq!(server.requests_to("update-teamnumber")[0].path, "update-teamnumber?team=25599");
+}
+
+#[tokio::test]
+async fn apriltag_decimation_round_trip() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    server.mock("pipeline-atindex", MockResponse::json(r#"{"fiducial_resdiv": 2.0}"#));
+    server.mock("update-pipeline", MockResponse::status(200));
+    let client = LimelightClient::new(server.config());
+
+    assert!(matches!(client.set_apriltag_decimation(5, false).await, Err(LimelightError::ConfigError(_))));
+    assert!(client.set_apriltag_decimation(3, true).await.unwrap());
+    let update = &server.requests_to("update-pipeline")[0];
+    assert_eq!(update.path, "update-pipeline?flush=1");
+    assert_eq!(update.body, br#"{"fiducial_resdiv":3}"#);
+
+    assert_eq!(client.get_apriltag_decimation().await.unwrap(), Some(2));
+    assert_eq!(server.requests_to("pipeline-atindex")[0].path, "pipeline-atindex?index=2");
+}
+
+#[tokio::test]
+async fn reload_pipeline_and_wait_times_out_on_frozen_ts() {
+    let server = MockServer::start().await;
+    server.mock("reload-pipeline", MockResponse::status(200));
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let client = LimelightClient::new(server.config());
+
+    // The mock always reports the same ts, so results never look like they resumed
+    let waited = client.reload_pipeline_and_wait(Duration::from_millis(150)).await;
+    assert!(matches!(waited, Err(LimelightError::TimeoutError)));
+    assert!(server.requests_to("results").len() >= 2);
+}
+
+#[tokio::test]
+async fn poll_gate_skips_requests_while_closed() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+
+    let clock = ManualClock::new();
+    let client = LimelightClient::with_clock(server.config(), clock.clone());
+    let enabled = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let gate = enabled.clone();
+    client.set_poll_gate(move || gate.load(std::sync::atomic::Ordering::SeqCst)).await;
+    let mut results = client.subscribe();
+    client.start().await.unwrap();
+
+    clock.advance(3);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(server.requests_to("results").is_empty());
+
+    enabled.store(true, std::sync::atomic::Ordering::SeqCst);
+    clock.advance(1);
+    timeout(Duration::from_secs(2), results.recv()).await.unwrap().unwrap();
+    assert_eq!(server.requests_to("results").len(), 1);
+
+    client.stop().await;
+    clock.advance(1);
+}
+
+#[tokio::test]
+async fn calibration_cache_follows_pipeline_and_refresh() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(r#"{"pipeline_id": 1}"#));
+    server.mock("cal-file", MockResponse::json(r#"{"resx": 1280, "resy": 800, "intrinsics": [900, 0, 640, 0, 900, 400, 0, 0, 1]}"#));
+    let client = LimelightClient::new(server.config());
+    let fetches = || server.requests_to("cal-file").len();
+
+    client.start().await.unwrap();
+    timeout(Duration::from_secs(2), client.next_result()).await.unwrap().unwrap();
+    assert_eq!(client.calibration().await.unwrap().fx(), Some(900.0));
+    client.calibration().await.unwrap();
+    assert_eq!(fetches(), 1);
+
+    server.mock("results", MockResponse::json(r#"{"pipeline_id": 2}"#));
+    while timeout(Duration::from_secs(2), client.next_result()).await.unwrap().unwrap().pipeline_id != Some(2) {}
+    client.calibration().await.unwrap();
+    client.calibration().await.unwrap();
+    assert_eq!(fetches(), 2);
+
+    client.refresh_calibration().await.unwrap();
+    client.calibration().await.unwrap();
+    client.stop().await;
+    assert_eq!(fetches(), 3);
+    assert!(server.requests_to("cal-eeprom").is_empty());
+}
+
+#[tokio::test]
+async fn latest_pose_falls_back_when_vision_is_missing_or_old() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(r#"{"botpose": [1.0, 2.0, 0.0, 0.0, 0.0, 90.0], "botpose_tagcount": 1}"#));
+    let client = LimelightClient::new(server.config());
+    let fallback = Pose3d { x: 5.0, y: 5.0, ..Default::default() };
+    let pose = || client.latest_pose(PoseOrigin::Field, MegaTagVersion::MegaTag1);
+
+    assert_eq!(pose().await, None);
+    client.set_fallback_pose(fallback, Duration::from_millis(300)).await;
+    assert_eq!(pose().await, Some(PoseReading { pose: fallback, stale: true }));
+
+    client.start().await.unwrap();
+    timeout(Duration::from_secs(2), client.next_result()).await.unwrap().unwrap();
+    client.stop().await;
+    client.join().await;
+    let fresh = pose().await.unwrap();
+    assert!(!fresh.stale);
+    assert_eq!((fresh.pose.x, fresh.pose.y), (1.0, 2.0));
+
+    // Past max_age the vision pose gives way to the fallback, unless there is none
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(pose().await, Some(PoseReading { pose: fallback, stale: true }));
+    client.clear_fallback_pose().await;
+    assert!(!pose().await.unwrap().stale);
+
+    // A frame without tags carries no vision pose
+    server.mock("results", MockResponse::json(r#"{"botpose": [1.0, 2.0, 0.0, 0.0, 0.0, 90.0], "botpose_tagcount": 0}"#));
+    client.set_fallback_pose(fallback, Duration::from_secs(10)).await;
+    client.start().await.unwrap();
+    while timeout(Duration::from_secs(2), client.next_result()).await.unwrap().unwrap().botpose_tagcount != Some(0) {}
+    client.stop().await;
+    assert_eq!(pose().await, Some(PoseReading { pose: fallback, stale: true }));
+}
+
+#[tokio::test]
+async fn latest_calibration_reports_quality() {
+    let server = MockServer::start().await;
+    server.mock(
+        "cal-latest",
+        MockResponse::json(r#"{"resx": 1280, "resy": 800, "intrinsics": [900, 0, 640, 0, 900, 400, 0, 0, 1], "reprojection_error": 0.42}"#),
+    );
+    let calibration = LimelightClient::new(server.config()).latest_calibration_result().await.unwrap();
+
+    assert_eq!(calibration.reprojection_error, Some(0.42));
+    assert!(calibration.is_calibration_acceptable(0.5));
+    assert!(!calibration.is_calibration_acceptable(0.3));
+}
+
+#[tokio::test]
+async fn verified_neural_network_upload_checks_reported_size() {
+    let server = MockServer::start().await;
+    server.mock("upload-nn", MockResponse::status(200));
+    server.mock("nn-info", MockResponse::json(r#"{"name": "detector.tflite", "size": 4}"#));
+    let client = LimelightClient::new(server.config());
+
+    assert!(client.upload_neural_network_verified("detector", b"tflt", Some(1)).await.unwrap());
+    assert_eq!(server.requests_to("nn-info")[0].path, "nn-info?type=detector&index=1");
+
+    let mismatch = client.upload_neural_network_verified("detector", b"truncated", Some(1)).await;
+    match mismatch {
+        Err(LimelightError::UploadVerificationFailed(message)) => assert_eq!(message, "device reports 4 bytes, uploaded 9"),
+        other => panic!("expected UploadVerificationFailed, got {:?}", other),
+    }
+
+    server.mock("nn-info", MockResponse::json(r#"{"name": "detector.tflite"}"#));
+    assert!(matches!(
+        client.upload_neural_network_verified("detector", b"tflt", None).await,
+        Err(LimelightError::UploadVerificationFailed(_))
+    ));
+    assert_eq!(server.requests_to("upload-nn").len(), 3);
+}
+
+#[tokio::test]
+async fn neural_network_models_are_listed() {
+    let server = MockServer::start().await;
+    server.mock("getnnmodelnames", MockResponse::json(r#"["coral_detector.tflite", "classifier.tflite"]"#));
+    let client = LimelightClient::new(server.config());
+
+    let models = client.get_neural_network_models().await.unwrap();
+    assert_eq!(models, vec!["coral_detector.tflite", "classifier.tflite"]);
+    let requests = server.requests_to("getnnmodelnames");
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+}
+
+#[tokio::test]
+async fn averaged_botpose_averages_consistent_frames() {
+    let server = MockServer::start().await;
+    server.mock_cycle(
+        "results",
+        vec![
+            MockResponse::json(r#"{"botpose": [1.0, 2.0, 0.0, 0.0, 0.0, 10.0], "botpose_tagcount": 2}"#),
+            MockResponse::json(r#"{"botpose": [1.1, 2.1, 0.0, 0.0, 0.0, 12.0], "botpose_tagcount": 2}"#),
+        ],
+    );
+    let client = LimelightClient::new(server.config());
+    client.start().await.unwrap();
+
+    let pose = client.averaged_botpose(PoseOrigin::Field, MegaTagVersion::MegaTag1, 4, Duration::from_secs(2)).await.unwrap();
+    client.stop().await;
+    assert!((pose.x - 1.05).abs() < 1e-9 && (pose.y - 2.05).abs() < 1e-9);
+    assert!((pose.yaw - 11.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn averaged_botpose_rejects_noisy_or_missing_frames() {
+    let server = MockServer::start().await;
+    server.mock_cycle(
+        "results",
+        vec![
+            MockResponse::json(r#"{"botpose": [1.0, 2.0, 0.0, 0.0, 0.0, 10.0], "botpose_tagcount": 1}"#),
+            MockResponse::json(r#"{"botpose": [2.0, 2.0, 0.0, 0.0, 0.0, 10.0], "botpose_tagcount": 1}"#),
+        ],
+    );
+    let client = LimelightClient::new(server.config());
+    assert!(matches!(
+        client.averaged_botpose(PoseOrigin::Field, MegaTagVersion::MegaTag1, 4, Duration::from_secs(2)).await,
+        Err(LimelightError::NotRunning)
+    ));
+    client.start().await.unwrap();
+
+    let noisy = client.averaged_botpose(PoseOrigin::Field, MegaTagVersion::MegaTag1, 4, Duration::from_secs(2)).await;
+    assert!(matches!(noisy, Err(LimelightError::InconsistentPose(_))), "got {:?}", noisy);
+
+    // Frames without tags never count as samples
+    server.mock("results", MockResponse::json(r#"{"botpose": [1.0, 2.0, 0.0, 0.0, 0.0, 10.0], "botpose_tagcount": 0}"#));
+    let starved = client.averaged_botpose(PoseOrigin::Field, MegaTagVersion::MegaTag1, 4, Duration::from_millis(200)).await;
+    client.stop().await;
+    assert!(matches!(starved, Err(LimelightError::TimeoutError)), "got {:?}", starved);
+}
+
+#[tokio::test]
+async fn measure_latency_summarizes_reported_latency() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(r#"{"cl": 10.0, "tl": 20.0}"#));
+    let client = LimelightClient::new(server.config());
+
+    assert!(matches!(client.measure_latency(0).await, Err(LimelightError::ConfigError(_))));
+    let stats = client.measure_latency(3).await.unwrap();
+    assert_eq!(stats.samples, 3);
+    assert_eq!((stats.min_ms, stats.max_ms, stats.mean_ms, stats.stddev_ms), (30.0, 30.0, 30.0, 0.0));
+    assert!(stats.mean_round_trip_ms >= 0.0);
+    assert_eq!(server.requests_to("results").len(), 3);
+}
+
+#[tokio::test]
+async fn missing_device_data_is_an_unexpected_response() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(r#"{"tx": 1.0}"#));
+    server.mock("status", MockResponse::json(r#"{"fps": 90}"#));
+    let client = LimelightClient::new(server.config());
+
+    assert!(matches!(client.measure_latency(2).await, Err(LimelightError::UnexpectedResponse(_))));
+    assert!(matches!(client.get_apriltag_decimation().await, Err(LimelightError::UnexpectedResponse(_))));
+    assert!(matches!(client.get_python_output_count().await, Err(LimelightError::UnexpectedResponse(_))));
+}
+
+#[tokio::test]
+async fn missing_ts_or_empty_batch_is_an_unexpected_response() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(r#"{"tx": 1.0}"#));
+    let client = LimelightClient::new(server.config());
+    assert!(matches!(client.get_device_time().await, Err(LimelightError::UnexpectedResponse(_))));
+
+    server.mock("results", MockResponse::json("[]"));
+    assert!(matches!(client.fetch_once().await, Err(LimelightError::UnexpectedResponse(_))));
+}
+
+#[tokio::test]
+async fn snapshots_are_archived_and_deleted() {
+    let server = MockServer::start().await;
+    server.mock("snapshotmanifest", MockResponse::json(r#"["front.png", "back.png"]"#));
+    server.mock("snapshot", MockResponse::bytes(b"image"));
+    server.mock("delete-snapshot", MockResponse::status(200));
+
+    let dir = std::env::temp_dir().join(format!("limelight-archive-{}", std::process::id()));
+    let client = LimelightClient::new(server.config());
+    let archive = client.auto_archive_snapshots(dir.clone(), Duration::from_millis(20), true).await.unwrap();
+    for _ in 0..100 {
+        if server.requests_to("delete-snapshot").len() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(archive.stop().await.unwrap(), 2);
+
+    assert_eq!(std::fs::read(dir.join("front.png")).unwrap(), b"image");
+    assert_eq!(std::fs::read(dir.join("back.png")).unwrap(), b"image");
+    assert_eq!(server.requests_to("snapshot").len(), 2);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn snapshot_archive_rejects_zero_poll_interval() {
+    let server = MockServer::start().await;
+    let dir = std::env::temp_dir().join(format!("limelight-archive-zero-{}", std::process::id()));
+    let client = LimelightClient::new(server.config());
+
+    let result = client.auto_archive_snapshots(dir.clone(), Duration::ZERO, false).await;
+    assert!(matches!(result, Err(LimelightError::ConfigError(_))));
+    assert!(!dir.exists());
+}
+
+#[tokio::test]
+async fn upload_rejects_unsupported_pipeline_type() {
+    let server = MockServer::start().await;
+    server.mock("hwreport", MockResponse::json(r#"{"pipelinetypes": ["pipe_fiducial", "pipe_color"]}"#));
+    server.mock("upload-pipeline", MockResponse::status(200));
+    let client = LimelightClient::new(server.config());
+
+    let types = client.get_supported_pipeline_types().await.unwrap();
+    assert_eq!(types, vec![PipelineType::Fiducial, PipelineType::Color]);
+
+    let detector = serde_json::json!({"pipeline_type": "pipe_neuraldetector"});
+    assert!(matches!(client.upload_pipeline(detector, Some(0)).await, Err(LimelightError::ConfigError(_))));
+    let fiducial = serde_json::json!({"pipeline_type": "pipe_fiducial"});
+    assert!(client.upload_pipeline(fiducial, Some(0)).await.unwrap());
+    assert_eq!(server.requests_to("upload-pipeline").len(), 1);
+}
+
+#[tokio::test]
+async fn switch_rejects_unsupported_pipeline_type() {
+    let server = MockServer::start().await;
+    server.mock("hwreport", MockResponse::json(r#"{"pipelinetypes": ["pipe_fiducial"]}"#));
+    server.mock("pipeline-atindex", MockResponse::json(r#"{"pipeline_type": "pipe_neuraldetector"}"#));
+    server.mock("pipeline-switch", MockResponse::status(200));
+    let client = LimelightClient::new(server.config());
+
+    assert!(matches!(client.switch_pipeline(4).await, Err(LimelightError::ConfigError(_))));
+    assert_eq!(server.requests_to("pipeline-atindex")[0].path, "pipeline-atindex?index=4");
+    assert!(server.requests_to("pipeline-switch").is_empty());
+
+    server.mock("pipeline-atindex", MockResponse::json(r#"{"pipeline_type": "pipe_fiducial"}"#));
+    assert!(client.switch_pipeline(1).await.unwrap());
+    assert_eq!(server.requests_to("pipeline-switch").len(), 1);
+    assert_eq!(server.requests_to("hwreport").len(), 1);
+}
+
+#[tokio::test]
+async fn upload_skips_type_check_without_hardware_report() {
+    let server = MockServer::start().await;
+    server.mock("upload-pipeline", MockResponse::status(200));
+    let client = LimelightClient::new(server.config());
+
+    let detector = serde_json::json!({"pipeline_type": "pipe_neuraldetector"});
+    assert!(client.upload_pipeline(detector, Some(0)).await.unwrap());
+    assert_eq!(server.requests_to("hwreport").len(), 1);
+    assert_eq!(server.requests_to("upload-pipeline").len(), 1);
+}
+
+#[tokio::test]
+async fn supported_pipeline_types_are_fetched_once() {
+    let server = MockServer::start().await;
+    server.mock("hwreport", MockResponse::json(r#"{"pipelinetypes": ["pipe_fiducial"]}"#));
+    server.mock("upload-pipeline", MockResponse::status(200));
+    let client = LimelightClient::new(server.config());
+
+    for _ in 0..3 {
+        let fiducial = serde_json::json!({"pipeline_type": "pipe_fiducial"});
+        assert!(client.upload_pipeline(fiducial, None).await.unwrap());
+    }
+    assert_eq!(server.requests_to("hwreport").len(), 1);
+}
+
+#[tokio::test]
+async fn udp_broadcast_forwards_results_as_json() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let listener = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+    let client = LimelightClient::new(server.config());
+    let forwarder = client.start_udp_broadcast(listener.local_addr().unwrap()).await.unwrap();
+    client.start().await.unwrap();
+
+    let mut buf = vec![0u8; 65536];
+    let len = timeout(Duration::from_secs(2), listener.recv(&mut buf)).await.unwrap().unwrap();
+    client.stop().await;
+    forwarder.abort();
+
+    let value: serde_json::Value = serde_json::from_slice(&buf[..len]).unwrap();
+    assert_eq!(value["pipeline_id"], 2);
+    assert_eq!(value["Fiducial"][1]["fID"], 8);
+}
+
+#[tokio::test]
+async fn calibration_matching_default_does_not_count() {
+    let default = r#"{"intrinsics": [900, 0, 640, 0, 900, 400, 0, 0, 1]}"#;
+    let server = MockServer::start().await;
+    server.mock("cal-default", MockResponse::json(default));
+    server.mock("cal-eeprom", MockResponse::json(default));
+    let client = LimelightClient::new(server.config());
+
+    assert!(!client.is_calibrated().await.unwrap());
+    let waited = client.wait_for_calibration(Duration::from_millis(100)).await;
+    assert!(matches!(waited, Err(LimelightError::TimeoutError)));
+
+    server.mock("cal-file", MockResponse::json(r#"{"intrinsics": [912.5, 0, 641, 0, 911, 398, 0, 0, 1]}"#));
+    assert!(client.is_calibrated().await.unwrap());
+    client.wait_for_calibration(Duration::from_secs(1)).await.unwrap();
+}
+
+#[tokio::test]
+async fn target_webhook_posts_rate_limited_summaries() {
+    let camera = MockServer::start().await;
+    camera.mock("results", MockResponse::json(RESULTS_JSON));
+    let dashboard = MockServer::start().await;
+    dashboard.mock("hook", MockResponse::status(200));
+
+    let client = LimelightClient::new(camera.config());
+    client.set_target_webhook(format!("http://127.0.0.1:{}/hook", dashboard.config().port)).await;
+    client.start().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    client.stop().await;
+    client.clear_target_webhook().await;
+
+    let posts = dashboard.requests_to("hook");
+    assert_eq!(posts.len(), 1);
+    let summary: serde_json::Value = serde_json::from_slice(&posts[0].body).unwrap();
+    assert_eq!(summary["tx"], 1.5);
+    assert_eq!(summary["tag_ids"], serde_json::json!([7, 8]));
+}
+
+#[tokio::test]
+async fn target_webhook_omits_configured_headers() {
+    let camera = MockServer::start().await;
+    camera.mock("results", MockResponse::json(RESULTS_JSON));
+    let dashboard = MockServer::start().await;
+    dashboard.mock("hook", MockResponse::status(200));
+
+    let mut config = camera.config();
+    config.headers.insert("X-Gateway-Token".to_string(), "secret".to_string());
+    config.user_agent = Some("team-1234-robot".to_string());
+    let client = LimelightClient::new(config);
+    client.set_target_webhook(format!("http://127.0.0.1:{}/hook", dashboard.config().port)).await;
+    client.start().await.unwrap();
+    timeout(Duration::from_secs(2), async {
+        while dashboard.requests_to("hook").is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    client.stop().await;
+    client.clear_target_webhook().await;
+
+    assert_eq!(camera.requests_to("results")[0].headers.get("x-gateway-token").map(String::as_str), Some("secret"));
+    let post = &dashboard.requests_to("hook")[0];
+    assert!(!post.headers.contains_key("x-gateway-token"));
+    assert_ne!(post.headers.get("user-agent").map(String::as_str), Some("team-1234-robot"));
+}
+
+#[tokio::test]
+async fn tag_allowlist_subscription_filters_results() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let client = LimelightClient::new(server.config());
+    let mut wanted = client.subscribe_for_tags(vec![8, 12]);
+    let mut unwanted = client.subscribe_for_tags(vec![1, 2]);
+    client.start().await.unwrap();
+
+    let result = timeout(Duration::from_secs(2), wanted.recv()).await.unwrap().unwrap();
+    assert_eq!(result.pipeline_id, Some(2));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    client.stop().await;
+    assert!(unwanted.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn subscribe_distinct_skips_repeated_content() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let client = LimelightClient::new(server.config());
+    let mut distinct = client.subscribe_distinct();
+    client.start().await.unwrap();
+
+    let first = timeout(Duration::from_secs(2), distinct.recv()).await.unwrap().unwrap();
+    assert_eq!(first.pipeline_id, Some(2));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    client.stop().await;
+    assert!(distinct.try_recv().is_err());
+}
+
+#[test]
+fn config_builder_validates() {
+    let config = LimelightConfig::builder().host("10.12.34.11").port(5808).poll_interval_ms(20).build().unwrap();
+    assert_eq!(config.host, "10.12.34.11");
+    assert_eq!(config.port, 5808);
+    assert_eq!(config.poll_interval_ms, 20);
+
+    assert!(matches!(LimelightConfig::builder().host("").build(), Err(LimelightError::ConfigError(_))));
+    assert!(matches!(LimelightConfig::builder().poll_interval_ms(0).build(), Err(LimelightError::ConfigError(_))));
+}
+
+#[tokio::test]
+async fn request_timeout_is_configurable() {
+    let server = MockServer::start().await;
+    server.mock("upload-nn", MockResponse::status(200).with_delay(Duration::from_millis(300)));
+
+    let client = LimelightClient::new(server.config());
+    let err = client.upload_neural_network("detector", &[0u8; 64], None).await.unwrap_err();
+    assert!(matches!(err, LimelightError::TimeoutError));
+
+    let config = LimelightConfig { request_timeout_ms: 2000, ..server.config() };
+    let client = LimelightClient::new(config);
+    assert!(client.upload_neural_network("detector", &[0u8; 64], None).await.unwrap());
+
+    let config = LimelightConfig { upload_timeout_ms: Some(2000), ..server.config() };
+    let client = LimelightClient::new(config);
+    assert!(client.upload_neural_network("detector", &[0u8; 64], None).await.unwrap());
+}
+
+#[test]
+fn config_from_team_number() {
+    assert_eq!(LimelightConfig::from_team_number(1234).host, "10.12.34.11");
+    assert_eq!(LimelightConfig::from_team_number(9).host, "10.0.9.11");
+    assert_eq!(LimelightConfig::from_team_number(254).host, "10.2.54.11");
+    assert_eq!(LimelightConfig::from_team_number(10000).host, "10.100.0.11");
+    assert_eq!(LimelightConfig::from_team_number(1234).port, 5807);
+}
+
+#[cfg(feature = "mdns")]
+#[tokio::test]
+async fn unresolvable_mdns_host_names_the_host() {
+    let config = LimelightConfig { host: "no-such-limelight.local".to_string(), ..Default::default() };
+    let client = LimelightClient::new(config);
+    match client.get_status().await {
+        Err(LimelightError::ConfigError(message)) => assert!(message.contains("no-such-limelight.local")),
+        other => panic!("expected ConfigError, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[tokio::test]
+async fn join_waits_for_poll_loop_to_exit() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let client = LimelightClient::new(server.config());
+    client.start().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    client.stop().await;
+    timeout(Duration::from_secs(2), client.join()).await.unwrap();
+    let polled = server.requests_to("results").len();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(server.requests_to("results").len(), polled);
+
+    // Nothing left to wait for
+    timeout(Duration::from_millis(100), client.join()).await.unwrap();
+}
+
+#[tokio::test]
+async fn stop_interrupts_backoff() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::status(500));
+    let config = LimelightConfig { max_backoff_ms: Some(30_000), ..server.config() };
+    let client = LimelightClient::new(config);
+    client.start().await.unwrap();
+
+    // Long enough for the pauses to grow to several seconds
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    let started = std::time::Instant::now();
+    client.stop().await;
+    timeout(Duration::from_secs(1), client.join()).await.expect("join waited out the backoff");
+    assert!(started.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn failed_polls_back_off_until_recovery() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::status(500));
+    let config = LimelightConfig { max_backoff_ms: Some(200), ..server.config() };
+    let client = LimelightClient::new(config);
+    let mut results = client.subscribe();
+    client.start().await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    let attempts = server.requests_to("results").len();
+    assert!(attempts > 1 && attempts < 10, "{} attempts", attempts);
+
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    timeout(Duration::from_secs(1), results.recv()).await.unwrap().unwrap();
+    client.stop().await;
+}
+
+#[tokio::test]
+async fn connection_state_reports_transitions_once() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let client = LimelightClient::new(server.config());
+    let mut states = client.subscribe_connection_state();
+    client.start().await.unwrap();
+
+    let wait = Duration::from_secs(2);
+    assert_eq!(timeout(wait, states.recv()).await.unwrap().unwrap(), ConnectionState::Connected);
+    server.mock("results", MockResponse::status(500));
+    assert_eq!(timeout(wait, states.recv()).await.unwrap().unwrap(), ConnectionState::Disconnected);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    assert_eq!(timeout(wait, states.recv()).await.unwrap().unwrap(), ConnectionState::Reconnected);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    client.stop().await;
+    assert!(states.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn results_stream_yields_polled_results() {
+    use futures_util::StreamExt;
+
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let client = LimelightClient::new(server.config());
+    let stream = client.get_results_stream();
+    client.start().await.unwrap();
+
+    let results: Vec<_> = timeout(Duration::from_secs(2), stream.take(3).collect()).await.unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|r| r.pipeline_id == Some(2)));
+    client.stop().await;
+}
+
+#[tokio::test]
+async fn next_result_ends_when_client_stops() {
+    // No results route, so nothing is ever published
+    let server = MockServer::start().await;
+    let client = LimelightClient::new(server.config());
+    client.start().await.unwrap();
+
+    let pending = tokio::spawn(client.next_result());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!pending.is_finished());
+    client.stop().await;
+    let outcome = timeout(Duration::from_secs(1), pending).await.expect("next_result hung after stop").unwrap();
+    assert!(matches!(outcome, Err(LimelightError::NotRunning)));
+}
+
+#[tokio::test]
+async fn fetch_once_leaves_client_idle() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let client = LimelightClient::new(server.config());
+
+    let result = client.fetch_once().await.unwrap();
+    assert_eq!(result.pipeline_id, Some(2));
+    assert!(client.get_latest_result().await.is_none());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(server.requests_to("results").len(), 1);
+}
+
+#[tokio::test]
+async fn websocket_results_feed_subscribers() {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+        for _ in 0..3 {
+            socket.send(Message::Text(RESULTS_JSON.to_string())).await.unwrap();
+        }
+        // Hold the socket open until the client goes away
+        while socket.next().await.is_some() {}
+    });
+
+    let config = LimelightConfig { host: "127.0.0.1".to_string(), port, ..Default::default() };
+    let client = LimelightClient::new(config);
+    let mut results = client.subscribe();
+    client.start_websocket().await.unwrap();
+
+    for _ in 0..3 {
+        let result = timeout(Duration::from_secs(2), results.recv()).await.unwrap().unwrap();
+        assert_eq!(result.pipeline_id, Some(2));
+    }
+    assert!(client.get_latest_result().await.is_some());
+    client.stop().await;
+    timeout(Duration::from_secs(2), client.join()).await.unwrap();
+}
+
+#[tokio::test]
+async fn websocket_upgrade_failure_falls_back_to_polling() {
+    let server = MockServer::start().await;
+    server.mock("results", MockResponse::json(RESULTS_JSON));
+    let client = LimelightClient::new(server.config());
+    let mut results = client.subscribe();
+    client.start_websocket().await.unwrap();
+
+    let result = timeout(Duration::from_secs(2), results.recv()).await.unwrap().unwrap();
+    assert_eq!(result.pipeline_id, Some(2));
+    client.stop().await;
+    assert!(server.requests_to("results").len() > 1);
+}
+
+#[tokio::test]
+async fn typed_status_tolerates_unknown_fields() {
+    let server = MockServer::start().await;
+    server.mock(
+        "status",
+        MockResponse::json(r#"{"temp": 51.5, "cpu": 32.0, "ram": 40, "finalFps": 90, "pipelineIndex": 2, "pipelineType": "pipe_fiducial", "uptime": 120.5, "snapshotMode": 0}"#),
+    );
+    let client = LimelightClient::new(server.config());
+
+    let status = client.get_status_typed().await.unwrap();
+    assert_eq!(status.cpu_temp, Some(51.5));
+    assert_eq!(status.ram_usage, Some(40.0));
+    assert_eq!(status.fps, Some(90.0));
+    assert_eq!(status.pipeline_index, Some(2));
+    assert_eq!(status.pipeline_type.as_deref(), Some("pipe_fiducial"));
+    assert_eq!(status.firmware_version, None);
+}
+
+#[tokio::test]
+async fn typed_status_accepts_duplicate_key_variants() {
+    let server = MockServer::start().await;
+    server.mock(
+        "status",
+        MockResponse::json(r#"{"fps": 30, "finalFps": 29.5, "version": "2024.10.2", "swversion": "2024.10", "temp": 48.0, "cpu_temp": 50.0, "pipelineIndex": 0.0}"#),
+    );
+    let client = LimelightClient::new(server.config());
+
+    let status = client.get_status_typed().await.unwrap();
+    assert_eq!(status.fps, Some(30.0));
+    assert_eq!(status.firmware_version.as_deref(), Some("2024.10.2"));
+    assert_eq!(status.cpu_temp, Some(50.0));
+    assert_eq!(status.pipeline_index, Some(0));
+}
+
+#[tokio::test]
+async fn typed_status_skips_fractional_pipeline_index() {
+    let server = MockServer::start().await;
+    server.mock("status", MockResponse::json(r#"{"pipelineIndex": 1.5, "fps": 90}"#));
+    let client = LimelightClient::new(server.config());
+
+    let status = client.get_status_typed().await.unwrap();
+    assert_eq!(status.pipeline_index, None);
+    assert_eq!(status.fps, Some(90.0));
+}
+
+#[tokio::test]
+async fn typed_hardware_report() {
+    let server = MockServer::start().await;
+    server.mock(
+        "hwreport",
+        MockResponse::json(r#"{"hwmodel": "LL4", "serial": 40213, "version": "2024.10.2", "sensor": {"name": "OV9281", "width": 1280, "height": 800}, "pipelinetypes": ["pipe_fiducial"], "extra": true}"#),
+    );
+    let client = LimelightClient::new(server.config());
+
+    let report = client.get_hardware_report_typed().await.unwrap();
+    assert_eq!(report.hardware_model.as_deref(), Some("LL4"));
+    assert_eq!(report.serial_number.as_deref(), Some("40213"));
+    assert_eq!(report.firmware_version.as_deref(), Some("2024.10.2"));
+    assert_eq!(report.build_date, None);
+    let sensor = report.sensor.unwrap();
+    assert_eq!((sensor.model.as_deref(), sensor.width, sensor.height), (Some("OV9281"), Some(1280), Some(800)));
+    assert_eq!(report.pipeline_types, vec!["pipe_fiducial"]);
+}
+
+#[tokio::test]
+async fn typed_hardware_report_tolerates_malformed_sensor_and_types() {
+    let server = MockServer::start().await;
+    server.mock(
+        "hwreport",
+        MockResponse::json(r#"{"hwmodel": "LL3G", "sensor": {"name": "OV9281", "width": 1280.0, "height": 800.5}, "pipelinetypes": ["pipe_fiducial", 3, null, "pipe_color"]}"#),
+    );
+    let client = LimelightClient::new(server.config());
+
+    let report = client.get_hardware_report_typed().await.unwrap();
+    assert_eq!(report.hardware_model.as_deref(), Some("LL3G"));
+    let sensor = report.sensor.unwrap();
+    assert_eq!((sensor.model.as_deref(), sensor.width, sensor.height), (Some("OV9281"), Some(1280), None));
+    assert_eq!(report.pipeline_types, vec!["pipe_fiducial", "pipe_color"]);
+
+    server.mock("hwreport", MockResponse::json(r#"{"sensor": "OV2311", "pipelinetypes": "pipe_fiducial"}"#));
+    let report = client.get_hardware_report_typed().await.unwrap();
+    assert_eq!(report.sensor.and_then(|sensor| sensor.model).as_deref(), Some("OV2311"));
+    assert!(report.pipeline_types.is_empty());
+
+    server.mock("hwreport", MockResponse::json(r#"{"sensor": [1, 2], "version": "2024.10"}"#));
+    let report = client.get_hardware_report_typed().await.unwrap();
+    assert_eq!(report.sensor, None);
+    assert_eq!(report.firmware_version.as_deref(), Some("2024.10"));
+}
+
+#[tokio::test]
+async fn typed_hardware_report_accepts_duplicate_key_variants() {
+    let server = MockServer::start().await;
+    server.mock(
+        "hwreport",
+        MockResponse::json(r#"{"model": "LL3", "hw_type": "3G", "serial": 7, "serialnumber": "0007", "build": "2024-10-01", "builddate": "2024-09-30"}"#),
+    );
+    let client = LimelightClient::new(server.config());
+
+    let report = client.get_hardware_report_typed().await.unwrap();
+    assert_eq!(report.hardware_model.as_deref(), Some("LL3"));
+    assert_eq!(report.serial_number.as_deref(), Some("7"));
+    assert_eq!(report.build_date.as_deref(), Some("2024-09-30"));
+    assert!(report.pipeline_types.is_empty());
+}
+
+#[tokio::test]
+async fn python_inputs_report_failed_constraint() {
+    let server = MockServer::start().await;
+    server.mock("update-pythoninputs", MockResponse::status(200));
+    let client = LimelightClient::new(server.config());
+
+    let message = |result: Result<bool, LimelightError>| match result {
+        Err(LimelightError::ConfigError(message)) => message,
+        other => panic!("expected ConfigError, got {:?}", other),
+    };
+    assert_eq!(message(client.update_python_inputs(&[1.0; 33]).await), "Python inputs must be 1..=32, got 33");
+    assert_eq!(message(client.update_python_inputs(&[]).await), "Python inputs must be 1..=32, got 0");
+    assert_eq!(message(client.update_python_inputs(&[1.0, f64::NAN]).await), "Python input 1 is NaN, inputs must be finite");
+    assert!(server.requests_to("update-pythoninputs").is_empty());
+
+    assert!(client.update_python_inputs(&[1.0, 2.0]).await.unwrap());
+}
+
+#[tokio::test]
+async fn full_orientation_posts_all_components_in_order() {
+    let server = MockServer::start().await;
+    server.mock("update-robotorientation", MockResponse::status(200));
+    let client = LimelightClient::new(server.config());
+
+    assert!(client.update_robot_orientation_full(90.0, 1.5, 2.0, 0.25, -3.0, 0.5).await.unwrap());
+    assert!(client.megatag2_orientation_fresh(Duration::from_secs(1)).await);
+    assert!(client.update_robot_orientation(45.0).await.unwrap());
+
+    let bodies: Vec<_> = server.requests_to("update-robotorientation").into_iter().map(|r| r.body).collect();
+    assert_eq!(bodies[0], b"[90.0,1.5,2.0,0.25,-3.0,0.5]");
+    assert_eq!(bodies[1], b"[45.0,0.0,0.0,0.0,0.0,0.0]");
+}
+
+#[tokio::test]
+async fn set_crop_clamps_and_rejects_inverted_or_empty_windows() {
+    let server = MockServer::start().await;
+    server.mock("update-pipeline", MockResponse::status(200));
+    let client = LimelightClient::new(server.config());
+
+    assert!(matches!(client.set_crop(0.5, -0.5, -1.0, 1.0).await, Err(LimelightError::ConfigError(_))));
+    assert!(matches!(client.set_crop(-1.0, 1.0, f64::NAN, 1.0).await, Err(LimelightError::ConfigError(_))));
+    // Both ends clamp to 1.0, leaving an empty window
+    assert!(matches!(client.set_crop(2.0, 3.0, -1.0, 1.0).await, Err(LimelightError::ConfigError(_))));
+    assert!(matches!(client.set_crop(-1.0, 1.0, 0.5, 0.5).await, Err(LimelightError::ConfigError(_))));
+    assert!(server.requests_to("update-pipeline").is_empty());
+
+    assert!(client.set_crop(-2.0, 0.5, -0.25, 3.0).await.unwrap());
+    let update = &server.requests_to("update-pipeline")[0];
+    assert_eq!(update.path, "update-pipeline?flush=0");
+    let body: serde_json::Value = serde_json::from_slice(&update.body).unwrap();
+    assert_eq!(body, serde_json::json!({"crop_x_min": -1.0, "crop_x_max": 0.5, "crop_y_min": -0.25, "crop_y_max": 1.0}));
+}
+
+#[tokio::test]
+async fn led_and_stream_modes_post_documented_values() {
+    let server = MockServer::start().await;
+    server.mock("update-pipeline", MockResponse::status(200));
+    let client = LimelightClient::new(server.config());
+
+    assert!(client.set_led_mode(LedMode::ForceBlink).await.unwrap());
+    assert!(client.set_stream_mode(StreamMode::PipSecondary).await.unwrap());
+    let updates = server.requests_to("update-pipeline");
+    assert_eq!(updates[0].body, br#"{"ledMode":2}"#);
+    assert_eq!(updates[1].body, br#"{"stream":2}"#);
+    assert_eq!(LedMode::Pipeline.value(), 0);
+    assert_eq!(StreamMode::Standard.value(), 0);
+}
+
+#[tokio::test]
+async fn download_snapshot_returns_bytes_or_not_found() {
+    let server = MockServer::start().await;
+    let client = LimelightClient::new(server.config());
+    match client.download_snapshot("missing").await {
+        Err(LimelightError::SnapshotNotFound(name)) => assert_eq!(name, "missing"),
+        other => panic!("expected SnapshotNotFound, got {:?}", other),
+    }
+
+    server.mock("snapshot", MockResponse::bytes(&[0xff, 0xd8, 0xff, 0xe0]));
+    assert_eq!(client.download_snapshot("calibration").await.unwrap(), vec![0xff, 0xd8, 0xff, 0xe0]);
+    assert_eq!(server.requests_to("snapshot").last().unwrap().path, "snapshot?snapname=calibration");
+}
@@ -0,0 +1,195 @@
+#![allow(dead_code)]
+
+use limelightlib_rust::LimelightConfig;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub delay: Option<Duration>,
+}
+
+impl MockResponse {
+    pub fn json(body: &str) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("Content-Type".into(), "application/json".into())],
+            body: body.as_bytes().to_vec(),
+            delay: None,
+        }
+    }
+
+    pub fn bytes(body: &[u8]) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("Content-Type".into(), "application/octet-stream".into())],
+            body: body.to_vec(),
+            delay: None,
+        }
+    }
+
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+            delay: None,
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    // Holds the response back, for exercising client timeouts
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+// Responses served in turn, wrapping around after the last
+struct Route {
+    responses: Vec<MockResponse>,
+    next: usize,
+}
+
+// Minimal in-process HTTP/1.1 server. Routes match on the path without its query
+// string; unmatched routes answer 404. Every request is recorded for assertions.
+pub struct MockServer {
+    pub port: u16,
+    routes: Arc<Mutex<HashMap<String, Route>>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockServer {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let routes = Arc::new(Mutex::new(HashMap::new()));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let accept_routes = routes.clone();
+        let accept_requests = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(Self::serve(stream, accept_routes.clone(), accept_requests.clone()));
+            }
+        });
+
+        Self { port, routes, requests }
+    }
+
+    pub fn mock(&self, path: &str, response: MockResponse) {
+        self.mock_cycle(path, vec![response]);
+    }
+
+    pub fn mock_cycle(&self, path: &str, responses: Vec<MockResponse>) {
+        assert!(!responses.is_empty(), "mock_cycle needs at least one response");
+        self.routes.lock().unwrap().insert(path.to_string(), Route { responses, next: 0 });
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn requests_to(&self, path: &str) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|r| r.path.split('?').next() == Some(path))
+            .collect()
+    }
+
+    pub fn config(&self) -> LimelightConfig {
+        LimelightConfig {
+            host: "127.0.0.1".to_string(),
+            port: self.port,
+            poll_interval_ms: 10,
+            ..Default::default()
+        }
+    }
+
+    async fn serve(
+        mut stream: TcpStream,
+        routes: Arc<Mutex<HashMap<String, Route>>>,
+        requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    ) {
+        let mut buffer = Vec::new();
+        loop {
+            let header_end = loop {
+                if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break pos + 4;
+                }
+                let mut chunk = [0u8; 4096];
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+                }
+            };
+
+            let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+            let mut lines = head.lines();
+            let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+            let method = request_line.next().unwrap_or_default().to_string();
+            let path = request_line.next().unwrap_or_default().trim_start_matches('/').to_string();
+            let headers: HashMap<String, String> = lines
+                .filter_map(|line| line.split_once(':'))
+                .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+                .collect();
+            let content_length: usize = headers
+                .get("content-length")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0);
+
+            while buffer.len() < header_end + content_length {
+                let mut chunk = [0u8; 4096];
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+                }
+            }
+            let body = buffer[header_end..header_end + content_length].to_vec();
+            buffer.drain(..header_end + content_length);
+
+            let route = path.split('?').next().unwrap_or_default().to_string();
+            requests.lock().unwrap().push(RecordedRequest { method, path, headers, body });
+            let response = match routes.lock().unwrap().get_mut(&route) {
+                Some(route) => {
+                    let response = route.responses[route.next % route.responses.len()].clone();
+                    route.next += 1;
+                    response
+                }
+                None => MockResponse::status(404),
+            };
+
+            if let Some(delay) = response.delay {
+                tokio::time::sleep(delay).await;
+            }
+            let mut out = format!("HTTP/1.1 {} Mock\r\nContent-Length: {}\r\n", response.status, response.body.len());
+            for (name, value) in &response.headers {
+                out.push_str(&format!("{}: {}\r\n", name, value));
+            }
+            out.push_str("\r\n");
+            let mut bytes = out.into_bytes();
+            bytes.extend_from_slice(&response.body);
+            if stream.write_all(&bytes).await.is_err() {
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use limelightlib_rust::{Pose2d, Pose3d, shared_tag_count, tag_set_changed, DetectorResult, FiducialResult, FirmwareInfo, LimelightResult, ResultView, TagFamily, TargetKind};
+
+fn fiducial(id: i32, fam: &str, ta: Option<f64>) -> FiducialResult {
+    FiducialResult {
+        f_id: Some(id),
+        fam: Some(fam.to_string()),
+        ta,
+        tx: Some(id as f64),
+        ty: Some(-(id as f64)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn tag_family_parses_firmware_strings() {
+    assert_eq!(TagFamily::from("36H11C"), TagFamily::Tag36h11);
+    assert_eq!(TagFamily::from("tag16h5"), TagFamily::Tag16h5);
+    assert_eq!(TagFamily::from("bogus"), TagFamily::Other("bogus".into()));
+    assert_eq!(TagFamily::Tag36h11.to_string(), "36h11");
+}
+
+#[test]
+fn fiducials_sorted_by_area_skip_missing() {
+    let result = LimelightResult {
+        fiducial: vec![
+            fiducial(1, "36H11C", Some(0.2)),
+            fiducial(2, "36H11C", None),
+            fiducial(3, "16H5C", Some(0.9)),
+        ],
+        ..Default::default()
+    };
+
+    let ids: Vec<_> = result.fiducials_by_area().iter().map(|f| f.f_id).collect();
+    assert_eq!(ids, vec![Some(3), Some(1)]);
+    assert_eq!(result.fiducials_of_family(&TagFamily::Tag36h11).len(), 2);
+}
+
+#[test]
+fn all_targets_flattens_result_types() {
+    let mut result = LimelightResult {
+        fiducial: vec![fiducial(4, "36H11C", Some(0.5))],
+        ..Default::default()
+    };
+    result.detector.push(Default::default());
+
+    let targets = result.all_targets();
+    assert_eq!(targets.len(), 1);
+    assert_eq!(targets[0].kind, TargetKind::Fiducial);
+    assert_eq!(targets[0].tx, 4.0);
+}
+
+#[test]
+fn as_view_follows_pipeline_type() {
+    let result = LimelightResult {
+        pipeline_type: Some("pipe_fiducial".into()),
+        botpose: Some(vec![1.0, 2.0, 0.0, 0.0, 0.0, 90.0]),
+        fiducial: vec![fiducial(4, "36H11C", Some(0.3))],
+        ..Default::default()
+    };
+    match result.as_view() {
+        ResultView::Fiducial { botpose, tags } => {
+            assert_eq!(botpose.map(|p| p.x), Some(1.0));
+            assert_eq!(tags.len(), 1);
+        }
+        other => panic!("unexpected view {other:?}"),
+    }
+
+    let unknown = LimelightResult { pipeline_type: Some("pipe_new".into()), ..Default::default() };
+    assert!(matches!(unknown.as_view(), ResultView::Unknown { pipeline_type: Some("pipe_new") }));
+}
+
+#[test]
+fn firmware_info_reads_report_and_compares_versions() {
+    let report = serde_json::json!({"swversion": "2024.10.2", "builddate": "2024-10-01", "hwmodel": "LL3G"});
+    let info = FirmwareInfo::from_hardware_report(&report);
+    assert_eq!(info.version.as_deref(), Some("2024.10.2"));
+    assert_eq!(info.hardware_model.as_deref(), Some("LL3G"));
+    assert!(info.version_at_least("2024.9"));
+    assert!(info.version_at_least("2024.10.2"));
+    assert!(!info.version_at_least("2025.0"));
+}
+
+#[test]
+fn detector_pixel_offset_from_center() {
+    let detection = DetectorResult { txp: Some(400.0), typ: Some(200.0), ..Default::default() };
+    assert_eq!(detection.pixel_offset_from_center(640, 480), Some((80.0, -40.0)));
+    assert_eq!(DetectorResult::default().pixel_offset_from_center(640, 480), None);
+}
+
+#[test]
+fn tag_set_continuity_between_frames() {
+    let frame = |ids: &[i32]| LimelightResult {
+        fiducial: ids.iter().map(|&id| fiducial(id, "36H11C", None)).collect(),
+        ..Default::default()
+    };
+    let prev = frame(&[1, 2, 3, 4]);
+    assert_eq!(shared_tag_count(&prev, &frame(&[3, 4, 5])), 2);
+    assert!(!tag_set_changed(&prev, &frame(&[3, 4, 5])));
+    assert!(tag_set_changed(&prev, &frame(&[4, 7, 8])));
+    assert!(!tag_set_changed(&frame(&[]), &frame(&[])));
+}
+
+#[test]
+fn malformed_entries_are_skipped() {
+    let json = r#"{
+        "tx": 4.0,
+        "Fiducial": [{"fID": 1, "ta": 0.5}, {"fID": "oops"}, {"fID": 3}],
+        "Detector": {"not": "an array"}
+    }"#;
+    let result: LimelightResult = serde_json::from_str(json).unwrap();
+    assert_eq!(result.tx, Some(4.0));
+    let ids: Vec<_> = result.fiducial.iter().map(|f| f.f_id).collect();
+    assert_eq!(ids, vec![Some(1), Some(3)]);
+    assert!(result.detector.is_empty());
+}
+
+#[test]
+fn target_centroid_averages_all_targets() {
+    let result = LimelightResult {
+        fiducial: vec![fiducial(1, "36H11C", Some(1.0)), fiducial(3, "36H11C", Some(3.0))],
+        ..Default::default()
+    };
+    assert_eq!(result.target_centroid(), Some((2.0, -2.0)));
+    assert_eq!(result.weighted_target_centroid(), Some((2.5, -2.5)));
+    assert_eq!(LimelightResult::default().target_centroid(), None);
+}
+
+#[test]
+fn botpose_accepts_array_or_object() {
+    let json = r#"{
+        "botpose": [1.0, 2.0, 0.0, 0.0, 0.0, 90.0, 20.0],
+        "botpose_wpiblue": {"x": 3.0, "y": 4.0, "z": 0.0, "roll": 0.0, "pitch": 0.0, "yaw": 45.0, "latency": 12.0}
+    }"#;
+    let result: LimelightResult = serde_json::from_str(json).unwrap();
+    assert_eq!(result.botpose.as_deref(), Some(&[1.0, 2.0, 0.0, 0.0, 0.0, 90.0, 20.0][..]));
+    assert_eq!(result.botpose_wpiblue.as_deref(), Some(&[3.0, 4.0, 0.0, 0.0, 0.0, 45.0, 12.0][..]));
+
+    let missing_yaw = r#"{"botpose": {"x": 1, "y": 2}, "tx": 3}"#;
+    let result: LimelightResult = serde_json::from_str(missing_yaw).unwrap();
+    assert_eq!(result.botpose, None);
+    assert_eq!(result.tx, Some(3.0));
+}
+
+#[test]
+fn has_any_pose_ignores_empty_arrays() {
+    let mut result = LimelightResult { botpose: Some(Vec::new()), ..Default::default() };
+    assert!(!result.has_any_pose());
+    result.botposeMT2_wpired = Some(vec![0.0; 6]);
+    assert!(result.has_any_pose());
+}
+
+#[test]
+fn pts_accepts_mixed_integer_and_float_coordinates() {
+    let json = r#"{
+        "Fiducial": [{"fID": 1, "pts": [[10, 20.5], [30.25, 40], [50, 60]]}],
+        "Detector": [{"classID": 0, "pts": [[1, 2], [3, 4]]}],
+        "Barcode": [{"data": "x", "pts": [[0.5, 1]]}]
+    }"#;
+    let result: LimelightResult = serde_json::from_str(json).unwrap();
+    assert_eq!(result.fiducial[0].pts, Some(vec![vec![10.0, 20.5], vec![30.25, 40.0], vec![50.0, 60.0]]));
+    assert_eq!(result.detector[0].pts, Some(vec![vec![1.0, 2.0], vec![3.0, 4.0]]));
+    assert_eq!(result.barcode[0].pts, Some(vec![vec![0.5, 1.0]]));
+}
+
+#[test]
+fn detector_aspect_ratio_from_corners() {
+    let pts = vec![vec![10.0, 20.0], vec![50.0, 20.0], vec![50.0, 40.0], vec![10.0, 40.0]];
+    let detection = DetectorResult { pts: Some(pts), ..Default::default() };
+    assert_eq!(detection.aspect_ratio(), Some(2.0));
+
+    let malformed = DetectorResult { pts: Some(vec![vec![1.0], vec![2.0, 3.0]]), ..Default::default() };
+    assert_eq!(malformed.aspect_ratio(), None);
+    assert_eq!(DetectorResult::default().aspect_ratio(), None);
+}
+
+#[test]
+fn content_hash_ignores_timing_fields() {
+    let a = LimelightResult { tx: Some(1.5), ts: Some(100.0), tl: Some(10.0), ..Default::default() };
+    let b = LimelightResult { tx: Some(1.5), ts: Some(200.0), tl: Some(12.0), ..Default::default() };
+    let c = LimelightResult { tx: Some(1.6), ts: Some(200.0), ..Default::default() };
+    assert_eq!(a.content_hash(), b.content_hash());
+    assert_ne!(a.content_hash(), c.content_hash());
+}
+
+#[test]
+fn valid_reads_v_as_bool() {
+    assert!(LimelightResult { v: Some(1.0), ..Default::default() }.valid());
+    assert!(!LimelightResult { v: Some(0.0), ..Default::default() }.valid());
+    assert!(!LimelightResult::default().valid());
+}
+
+#[test]
+fn botpose_accessors_name_each_array() {
+    let result = LimelightResult {
+        botpose: Some(vec![1.0, 2.0, 0.1, 0.0, 5.0, 90.0, 20.0, 2.0]),
+        botposeMT2_wpired: Some(vec![3.0, 4.0, 0.0, 0.0, 0.0, -45.0]),
+        botpose_wpiblue: Some(vec![1.0, 2.0]),
+        ..Default::default()
+    };
+    assert_eq!(result.botpose_as_pose3d(), Some(Pose3d::new(1.0, 2.0, 0.1, 0.0, 5.0, 90.0)));
+    assert_eq!(result.botpose_as_pose2d(), Some(Pose2d::new(1.0, 2.0, 90.0)));
+    assert_eq!(result.botpose_mt2_wpired_as_pose2d(), Some(Pose2d::new(3.0, 4.0, -45.0)));
+    assert_eq!(result.botpose_wpiblue_as_pose3d(), None);
+    assert_eq!(result.botpose_mt2_as_pose3d(), None);
+}
+
+#[test]
+fn result_round_trips_through_json() {
+    let json = r#"{
+        "pipeline_id": 1,
+        "pipeline_type": "pipe_fiducial",
+        "tx": 1.5,
+        "v": 1,
+        "botpose_orb_wpiblue": [1.0, 2.0, 0.0, 0.0, 0.0, 90.0, 25.0],
+        "botpose_tagcount": 1,
+        "Fiducial": [{"fID": 7, "fam": "36H11C", "ta": 0.5, "pts": [[1.0, 2.0], [3.0, 4.0]]}],
+        "Detector": [{"class": "note", "classID": 0, "conf": 0.9}],
+        "Classifier": [{"class": "red", "classID": 2}],
+        "Barcode": [{"fam": "QR", "data": "hello"}],
+        "Retro": [{"tx": -3.0, "ty": 1.0}]
+    }"#;
+    let parsed: LimelightResult = serde_json::from_str(json).unwrap();
+    let serialized = serde_json::to_string(&parsed).unwrap();
+    assert!(serialized.contains("\"botpose_orb_wpiblue\"") && serialized.contains("\"fID\":7"));
+    let reparsed: LimelightResult = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(reparsed, parsed);
+}
+
+#[test]
+fn capture_timestamp_subtracts_total_latency() {
+    let received_at = std::time::Instant::now();
+    let result = LimelightResult { cl: Some(12.5), tl: Some(7.5), ..Default::default() };
+    assert_eq!(result.total_latency_ms(), Some(20.0));
+    assert_eq!(result.capture_timestamp(received_at), Some(received_at - std::time::Duration::from_millis(20)));
+
+    let partial = LimelightResult { cl: Some(12.5), ..Default::default() };
+    assert_eq!(partial.total_latency_ms(), None);
+    assert_eq!(partial.capture_timestamp(received_at), None);
+}
+
+#[test]
+fn valid_target_count_spans_target_types() {
+    let result = LimelightResult {
+        v: Some(1.0),
+        fiducial: vec![fiducial(1, "36H11C", None), fiducial(2, "36H11C", None)],
+        detector: vec![DetectorResult::default()],
+        classifier: vec![Default::default()],
+        ..Default::default()
+    };
+    assert!(result.has_valid_target());
+    assert_eq!(result.valid_target_count(), 4);
+    assert_eq!(LimelightResult::default().valid_target_count(), 0);
+    let barcode_only = LimelightResult { barcode: vec![Default::default()], ..Default::default() };
+    assert_eq!(barcode_only.valid_target_count(), 0);
+}
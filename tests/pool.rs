@@ -0,0 +1,81 @@
+mod common;
+
+use common::{MockResponse, MockServer};
+use limelightlib_rust::{LimelightClient, LimelightGroup, LimelightPool, MountOffset};
+use tokio::time::{timeout, Duration};
+
+#[tokio::test]
+async fn fused_target_angle_weights_by_area_and_mount() {
+    let left = MockServer::start().await;
+    left.mock("results", MockResponse::json(r#"{"v": 1, "tx": 10.0, "ty": 0.0, "ta": 1.0}"#));
+    let right = MockServer::start().await;
+    right.mock("results", MockResponse::json(r#"{"v": 1, "tx": -10.0, "ty": 2.0, "ta": 3.0}"#));
+
+    let left_client = LimelightClient::new(left.config());
+    let right_client = LimelightClient::new(right.config());
+    let mut pool = LimelightPool::new();
+    pool.add("left", left_client.clone(), MountOffset { yaw_degrees: -5.0, pitch_degrees: 0.0 });
+    pool.add("right", right_client.clone(), MountOffset { yaw_degrees: 5.0, pitch_degrees: 0.0 });
+    assert_eq!(pool.fused_target_angle(), None);
+
+    pool.start_all().await.unwrap();
+    let wait = Duration::from_secs(2);
+    timeout(wait, left_client.next_result()).await.unwrap().unwrap();
+    timeout(wait, right_client.next_result()).await.unwrap().unwrap();
+    pool.stop_all().await;
+
+    let (tx, ty) = pool.fused_target_angle().unwrap();
+    assert!((tx - (5.0 - 15.0) / 4.0).abs() < 1e-9);
+    assert!((ty - 1.5).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn merged_subscription_tags_results_by_label() {
+    let front = MockServer::start().await;
+    front.mock("results", MockResponse::json(r#"{"v": 1, "pipeline_id": 1}"#));
+    let back = MockServer::start().await;
+    back.mock("results", MockResponse::json(r#"{"v": 1, "pipeline_id": 2}"#));
+
+    let mut pool = LimelightPool::new();
+    pool.add("front", LimelightClient::new(front.config()), MountOffset::default());
+    pool.add("back", LimelightClient::new(back.config()), MountOffset::default());
+    let mut merged = pool.subscribe();
+    pool.start_all().await.unwrap();
+
+    let mut seen = std::collections::HashSet::new();
+    while seen.len() < 2 {
+        let (label, _) = timeout(Duration::from_secs(2), merged.recv()).await.unwrap().unwrap();
+        seen.insert(label);
+    }
+    pool.stop_all().await;
+    assert_eq!(pool.get_latest("front").unwrap().pipeline_id, Some(1));
+}
+
+#[tokio::test]
+async fn group_merges_labelled_cameras() {
+    let front = MockServer::start().await;
+    front.mock("results", MockResponse::json(r#"{"v": 1, "pipeline_id": 1}"#));
+    let back = MockServer::start().await;
+    back.mock("results", MockResponse::json(r#"{"v": 1, "pipeline_id": 2}"#));
+
+    let mut group = LimelightGroup::new();
+    group.add("front", LimelightClient::new(front.config()));
+    group.add("back", LimelightClient::new(back.config()));
+    group.add("back", LimelightClient::new(back.config()));
+    assert_eq!(group.labels().collect::<Vec<_>>(), vec!["front", "back"]);
+    let mut merged = group.subscribe();
+    assert!(group.get_latest("front").is_none());
+    group.start_all().await.unwrap();
+
+    let mut seen = std::collections::HashSet::new();
+    while seen.len() < 2 {
+        let (label, result) = timeout(Duration::from_secs(2), merged.recv()).await.unwrap().unwrap();
+        let expected = if label == "front" { 1 } else { 2 };
+        assert_eq!(result.pipeline_id, Some(expected));
+        seen.insert(label);
+    }
+    group.stop_all().await;
+
+    assert_eq!(group.get_latest("back").unwrap().pipeline_id, Some(2));
+    assert!(group.get_latest("side").is_none());
+}
@@ -0,0 +1,93 @@
+use limelightlib_rust::{interpolate_pose, scaled_std_devs, FieldHalf, Pose2d, Pose3d, PoseStdDevs};
+
+fn assert_pose_eq(actual: Pose3d, expected: Pose3d) {
+    let pairs = [
+        (actual.x, expected.x),
+        (actual.y, expected.y),
+        (actual.z, expected.z),
+        (actual.roll, expected.roll),
+        (actual.pitch, expected.pitch),
+        (actual.yaw, expected.yaw),
+    ];
+    for (a, e) in pairs {
+        assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn relative_to_removes_shared_transform() {
+    let a = Pose3d::new(1.0, 2.0, 0.0, 0.0, 0.0, 90.0);
+    let b = Pose3d::new(1.0, 3.0, 0.0, 0.0, 0.0, 135.0);
+
+    // One meter along world +y is one meter along A's +x once A is rotated 90 degrees
+    assert_pose_eq(a.relative_to(&b), Pose3d::new(1.0, 0.0, 0.0, 0.0, 0.0, 45.0));
+    assert_pose_eq(a.relative_to(&a), Pose3d::default());
+}
+
+#[test]
+fn relative_to_is_inverted_by_swapping_frames() {
+    let a = Pose3d::new(0.5, -1.0, 2.0, 10.0, -20.0, 30.0);
+    let b = Pose3d::new(-0.5, 4.0, 1.0, -5.0, 15.0, -60.0);
+
+    // (a^-1 b)^-1 == b^-1 a
+    let inverted = a.relative_to(&b).relative_to(&Pose3d::default());
+    assert_pose_eq(inverted, b.relative_to(&a));
+}
+
+#[test]
+fn interpolate_pose_takes_shortest_yaw_path() {
+    let a = Pose3d::new(0.0, 0.0, 0.0, 0.0, 0.0, 170.0);
+    let b = Pose3d::new(2.0, 4.0, 0.0, 0.0, 0.0, -170.0);
+    let mid = interpolate_pose(&a, 1.0, &b, 2.0, 1.5);
+    assert_pose_eq(mid, Pose3d::new(1.0, 2.0, 0.0, 0.0, 0.0, -180.0));
+
+    let quarter = interpolate_pose(&a, 1.0, &b, 2.0, 1.25);
+    assert_pose_eq(quarter, Pose3d::new(0.5, 1.0, 0.0, 0.0, 0.0, 175.0));
+    assert_pose_eq(interpolate_pose(&a, 1.0, &b, 2.0, 5.0), b);
+}
+
+#[test]
+fn nt_array_round_trips() {
+    let pose = Pose3d::new(1.0, -2.0, 0.5, 1.0, 2.0, 179.0);
+    let array = pose.to_nt_array(25.0);
+    assert_eq!(array[6], 25.0);
+    assert_eq!(Pose3d::from_nt_array(&array), Some(pose));
+    assert_eq!(Pose3d::from_nt_array(&array[..5]), None);
+}
+
+#[test]
+fn std_devs_scale_with_distance_and_tag_count() {
+    let base = PoseStdDevs { x: 0.1, y: 0.1, yaw: 2.0 };
+    let near_many = scaled_std_devs(&base, 1.0, 4);
+    let far_one = scaled_std_devs(&base, 4.0, 1);
+    assert!((near_many.x - 0.125).abs() < 1e-12);
+    assert!((far_one.yaw - 34.0).abs() < 1e-12);
+    assert!(scaled_std_devs(&base, 2.0, 0).x.is_infinite());
+}
+
+#[test]
+fn std_devs_never_drop_below_base_for_close_tags() {
+    let base = PoseStdDevs { x: 0.1, y: 0.2, yaw: 2.0 };
+    assert_eq!(scaled_std_devs(&base, 0.0, 1), base);
+    assert_eq!(scaled_std_devs(&base, -1.0, 3), base);
+
+    let close = scaled_std_devs(&base, 0.5, 1);
+    assert!((close.x - 0.125).abs() < 1e-12);
+    assert!((close.yaw - 2.5).abs() < 1e-12);
+    let close_many = scaled_std_devs(&base, 0.5, 4);
+    assert!(close_many.x > base.x && close_many.x < close.x);
+}
+
+#[test]
+fn field_half_splits_at_centerline() {
+    let length = 16.54;
+    assert_eq!(Pose3d::new(2.0, 4.0, 0.0, 0.0, 0.0, 0.0).field_half(length), FieldHalf::Blue);
+    assert_eq!(Pose3d::new(14.0, 4.0, 0.0, 0.0, 0.0, 0.0).field_half(length), FieldHalf::Red);
+}
+
+#[test]
+fn to_pose2d_keeps_planar_components() {
+    let pose = Pose3d::new(1.5, -2.0, 0.3, 4.0, 5.0, 135.0);
+    assert_eq!(pose.heading_degrees(), 135.0);
+    assert_eq!(pose.to_pose2d(), Pose2d::new(1.5, -2.0, 135.0));
+}
@@ -0,0 +1,33 @@
+use limelightlib_rust::{LimelightResult, RecordedFrame, SessionReplayer};
+use tokio::time::{Duration, Instant};
+
+fn frame(offset_ms: u64, pipeline_id: i32) -> RecordedFrame {
+    RecordedFrame {
+        offset: Duration::from_millis(offset_ms),
+        result: LimelightResult { pipeline_id: Some(pipeline_id), ..Default::default() },
+    }
+}
+
+#[tokio::test]
+async fn replay_delivers_frames_in_offset_order() {
+    let replayer = SessionReplayer::new(vec![frame(20, 3), frame(0, 1), frame(10, 2), frame(10, 4)]);
+    let mut first = replayer.subscribe();
+    let mut second = replayer.subscribe();
+    replayer.run().await;
+
+    for results in [&mut first, &mut second] {
+        let mut ids = Vec::new();
+        while let Ok(result) = results.try_recv() {
+            ids.push(result.pipeline_id.unwrap());
+        }
+        assert_eq!(ids, vec![1, 2, 4, 3]);
+    }
+}
+
+#[tokio::test]
+async fn strict_ordering_reproduces_frame_timing() {
+    let replayer = SessionReplayer::new(vec![frame(0, 1), frame(60, 2)]).strict_ordering(true);
+    let started = Instant::now();
+    replayer.run().await;
+    assert!(started.elapsed() >= Duration::from_millis(60));
+}
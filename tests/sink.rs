@@ -0,0 +1,20 @@
+use limelightlib_rust::{CsvColumn, CsvSink, LimelightResult};
+
+#[test]
+fn csv_sink_writes_header_and_rows() {
+    let result = LimelightResult {
+        tx: Some(1.5),
+        botpose_tagcount: Some(2),
+        cl: Some(10.0),
+        tl: Some(5.0),
+        ..Default::default()
+    };
+
+    let columns = vec![CsvColumn::Timestamp, CsvColumn::Tx, CsvColumn::Ty, CsvColumn::TagCount, CsvColumn::LatencyMs];
+    let mut sink = CsvSink::with_columns(Vec::new(), columns);
+    sink.write_row(&result, 100.25).unwrap();
+    sink.write_row(&LimelightResult::default(), 101.0).unwrap();
+    let output = String::from_utf8(sink.close().unwrap()).unwrap();
+
+    assert_eq!(output, "timestamp,tx,ty,tagcount,latency_ms\n100.25,1.5,,2,15\n101,,,,\n");
+}